@@ -0,0 +1,224 @@
+app-name = Daily Time Keeper
+
+## Top panel
+
+new-tag-hint = Name des Tags (Strg+N)
+add-new-tag = Neuen Tag hinzufügen
+existing-tags = Vorhanden:
+archived-suggestion = { $name } (archiviert)
+minute-rounding-scale = Rundungsschritt:
+minute-rounding = Minuten runden
+dark-mode = Dunkles Design
+idle-after = Inaktiv nach:
+idle-after-hint = Fragt nach so vielen Minuten Abwesenheit bei laufendem Timer, was mit der Zeit geschehen soll. 0 schaltet es ab.
+minutes-suffix = { " " }Min.
+durations = Dauern:
+decimal-places = { " " }Stellen
+language = Sprache:
+language-system = System
+http-api = HTTP-API
+http-api-hint = Stellt Tags, Zeitabschnitte und Berichte als JSON auf 127.0.0.1 unter diesem Port bereit, für Clients mit dem Token.
+copy-token = Token kopieren
+http-api-unavailable = HTTP-API nicht verfügbar
+reports = Berichte
+charts = Diagramme
+invoices = Rechnungen
+archived-tags = Archivierte Tags
+sort-tags = Tags sortieren:
+filter = Filter:
+filter-hint = Tag oder Notiz (Strg+F)
+from = Von:
+to = Bis:
+clear-filter = Filter leeren
+
+## Choices
+
+sort-manual = Manuell
+sort-name = Name
+sort-recent-use = Zuletzt verwendet
+duration-decimal = Dezimalstunden
+duration-hours-minutes = H:MM
+duration-hours-minutes-seconds = H:MM:SS
+hour-format-24 = 24 Stunden
+hour-format-12 = 12 Stunden
+period-daily = Täglich
+period-weekly = Wöchentlich
+period-monthly = Monatlich
+
+## Tag list
+
+drag-to-reorder = Zum Umsortieren ziehen
+start = Start
+stop = Stopp
+double-click-to-rename = Doppelklick zum Umbenennen
+total-hours = Stunden gesamt: { $hours }
+total-hours-with-subtags = Stunden gesamt: { $hours } (mit Unter-Tags: { $rolled-up-hours })
+earnings-amount = Einnahmen: { $amount } { $currency }
+archive-tag = Tag archivieren
+settings = Einstellungen
+pin = Anheften
+unpin = Lösen
+segment-hours = Stunden: { $hours }
+note-hint = Notiz
+invoiced = Abgerechnet #{ $number }
+remove-time-segment = Zeitabschnitt entfernen
+history-segment = { $date } { $start } - { $end }  Stunden: { $hours }
+history-segment-note = Notiz: { $note }
+tag-not-renamed = Tag nicht umbenannt
+tag-name-empty = Tag-Namen dürfen nicht leer sein
+tag-name-taken = Es gibt bereits einen Tag namens „{ $name }“
+tag-name-taken-archived = Es gibt bereits einen archivierten Tag namens „{ $name }“
+
+## Tag settings
+
+color = Farbe:
+default-color = Standard
+icon = Symbol:
+icon-hint = Emoji
+parent = Übergeordnet:
+no-parent = Keiner
+budget = Budget:
+no-budget = Kein Budget
+budget-hours = Stunden:
+budget-target = Ziel
+budget-cap = Obergrenze
+budget-notify = Benachrichtigen
+billable = Abrechenbar
+hourly-rate = Stundensatz:
+client = Kunde:
+billing-rounding = Rundung:
+billing-rounding-global = Wie oben
+billing-rounding-off = Aus
+billing-rounding-own = Eigene Skala
+budget-target-reached = { $tag } hat sein Ziel erreicht
+budget-cap-exceeded = { $tag } hat sein Budget überschritten
+budget-notification-body = { $kind ->
+    [cap] Obergrenze von { $hours } Stunden ({ $period }) für { $tag }
+   *[target] Ziel von { $hours } Stunden ({ $period }) für { $tag }
+}
+
+## Bottom panel
+
+clear-session = Sitzung leeren
+today-hours = Heute: { $hours } / { $expected-hours }
+balance = Saldo: { $hours }
+work-hours = Arbeitszeiten
+pomodoro = Pomodoro
+pomodoro-work = Arbeit:
+pomodoro-break = Pause:
+pomodoro-work-left = { $tag }: noch { $time }
+pomodoro-break-left = Pause: noch { $time }
+monday = Montag
+tuesday = Dienstag
+wednesday = Mittwoch
+thursday = Donnerstag
+friday = Freitag
+saturday = Samstag
+sunday = Sonntag
+
+## Pomodoro
+
+completed-pomodoros = Heute abgeschlossene Pomodoros
+pomodoro-completed = Pomodoro für { $tag } abgeschlossen
+break-started = Zeit für { $minutes } Minuten Pause
+break-over = Pause vorbei
+break-over-body = Starte einen Tag für den nächsten Pomodoro
+
+## Breaks
+
+break-time = Pausen: { $hours }
+start-break = Pause beginnen
+end-break = Pause beenden
+break-rules = Pausenregeln
+break-rule-after = Mehr als
+break-rule-at-least = Arbeit erfordern mindestens
+add-break-rule = Regel hinzufügen
+minimum-break = Pausen zählen ab:
+max-hours-without-break = Höchstens ohne Pause:
+hours-suffix = { " " }Std.
+break-too-short = Mehr als { $hours } Stunden Arbeit erfordern { $required-minutes } Minuten Pause, { $taken-minutes } genommen
+break-too-late = Mehr als { $hours } Stunden ohne Pause gearbeitet
+
+## Reminders
+
+reminders = Erinnerungen
+auto-stop-at = Laufende Tags stoppen um
+long-run-after = Benachrichtigen, wenn ein Tag länger läuft als
+day-end-reminder-at = An den Tagesabschluss erinnern um
+auto-stopped = Tags automatisch gestoppt
+long-run = { $tag } läuft noch
+long-run-body = Er läuft seit mehr als { $hours } Stunden
+day-end-reminder = Zeit für den Tagesabschluss
+day-end-reminder-body = Prüfe die heutigen Zeitabschnitte und leere die Sitzung
+
+## Reports
+
+rounded-hours = Gerundete Stunden
+include-subtags = Unter-Tags einbeziehen
+export-csv = Als CSV exportieren
+report-exported = Bericht exportiert
+saved-to = Gespeichert unter { $path }
+period = Zeitraum
+total = Gesamt
+earnings = Einnahmen
+billable-hours = Abrechenbare Stunden
+non-billable-hours = Nicht abrechenbare Stunden
+earnings-in-currency = Einnahmen ({ $currency })
+
+## Charts
+
+today = Heute
+hours-per-day-weeks = Stunden pro Tag, Wochen:
+
+## Invoices
+
+invoices-need-billable-tag = Markiere einen Tag in seinen Einstellungen als abrechenbar, um Rechnungen zu erstellen.
+invoice-client = Kunde
+invoice-from = Von
+invoice-to = Bis
+line-items = Positionen
+per-segment = Pro Zeitabschnitt
+per-day = Pro Tag
+enter-invoice-period = Gib den Rechnungszeitraum im Format { $format } ein.
+invoice-summary = Rechnung { $number }: { $line-count ->
+    [one] 1 Position
+   *[other] { $line-count } Positionen
+}, { $hours } Stunden
+amount-due = Fälliger Betrag: { $amount } { $currency }
+generate-invoice = Rechnung erstellen
+invoice-created = Rechnung { $number } erstellt
+invoice-saved-to = Gespeichert unter { $html-path } und { $pdf-path }
+
+## Archived tags
+
+no-archived-tags = Keine archivierten Tags.
+archived-hours = { $hours } Stunden
+confirm-purge = Den Tag und seinen gesamten Verlauf löschen?
+purge = Löschen
+cancel = Abbrechen
+unarchive = Wiederherstellen
+
+## Idle time
+
+idle-time = Abwesenheit
+idle-interval = Du warst von { $start } bis { $end } ({ $hours }) bei laufendem Timer abwesend.
+keep-idle-time = Behalten
+discard-idle-time = Verwerfen
+reassign-idle-time = Umbuchen
+
+## Command palette
+
+command-palette = Befehlspalette
+command-palette-hint = Tag oder Aktion eingeben
+no-matches = Keine Treffer
+command-start-tag = { $tag } starten
+command-stop-tag = { $tag } stoppen
+command-stop-all = Alle Tags stoppen
+command-new-tag = Neuer Tag
+command-filter = Tags filtern
+command-open-reports = Berichte öffnen
+command-open-charts = Diagramme öffnen
+command-open-invoices = Rechnungen öffnen
+command-open-archive = Archivierte Tags öffnen
+command-open-work-hours = Arbeitszeiten öffnen
+command-toggle-dark-mode = Dunkles Design umschalten
@@ -0,0 +1,224 @@
+app-name = Daily Time Keeper
+
+## Top panel
+
+new-tag-hint = Enter Tag Name (Ctrl+N)
+add-new-tag = Add New Tag
+existing-tags = Existing:
+archived-suggestion = { $name } (archived)
+minute-rounding-scale = Minute Rounding Scale:
+minute-rounding = Minute Rounding
+dark-mode = Dark Mode
+idle-after = Idle After:
+idle-after-hint = Asks what to do with the time once you are back from this many minutes away while a timer runs. 0 turns it off.
+minutes-suffix = { " " }min
+durations = Durations:
+decimal-places = { " " }places
+language = Language:
+language-system = System
+http-api = HTTP API
+http-api-hint = Serves tags, segments and reports as JSON on 127.0.0.1 at this port, for clients sending the token.
+copy-token = Copy Token
+http-api-unavailable = HTTP API unavailable
+reports = Reports
+charts = Charts
+invoices = Invoices
+archived-tags = Archived Tags
+sort-tags = Sort Tags:
+filter = Filter:
+filter-hint = Tag or note (Ctrl+F)
+from = From:
+to = To:
+clear-filter = Clear Filter
+
+## Choices
+
+sort-manual = Manual
+sort-name = Name
+sort-recent-use = Recent Use
+duration-decimal = Decimal Hours
+duration-hours-minutes = H:MM
+duration-hours-minutes-seconds = H:MM:SS
+hour-format-24 = 24-hour
+hour-format-12 = 12-hour
+period-daily = Daily
+period-weekly = Weekly
+period-monthly = Monthly
+
+## Tag list
+
+drag-to-reorder = Drag to reorder
+start = Start
+stop = Stop
+double-click-to-rename = Double-click to rename
+total-hours = Total Hours: { $hours }
+total-hours-with-subtags = Total Hours: { $hours } (with subtags: { $rolled-up-hours })
+earnings-amount = Earnings: { $amount } { $currency }
+archive-tag = Archive Tag
+settings = Settings
+pin = Pin
+unpin = Unpin
+segment-hours = Hours: { $hours }
+note-hint = Note
+invoiced = Invoiced #{ $number }
+remove-time-segment = Remove Time Segment
+history-segment = { $date } { $start } - { $end }  Hours: { $hours }
+history-segment-note = Note: { $note }
+tag-not-renamed = Tag not renamed
+tag-name-empty = Tag names can't be empty
+tag-name-taken = A tag named "{ $name }" already exists
+tag-name-taken-archived = An archived tag named "{ $name }" already exists
+
+## Tag settings
+
+color = Color:
+default-color = Default
+icon = Icon:
+icon-hint = Emoji
+parent = Parent:
+no-parent = None
+budget = Budget:
+no-budget = No Budget
+budget-hours = Hours:
+budget-target = Target
+budget-cap = Cap
+budget-notify = Notify
+billable = Billable
+hourly-rate = Hourly Rate:
+client = Client:
+billing-rounding = Rounding:
+billing-rounding-global = Like Top Panel
+billing-rounding-off = Off
+billing-rounding-own = Own Scale
+budget-target-reached = { $tag } reached its target
+budget-cap-exceeded = { $tag } exceeded its budget
+budget-notification-body = { $kind ->
+    [cap] { $hours } hour { $period } cap on { $tag }
+   *[target] { $hours } hour { $period } target on { $tag }
+}
+
+## Bottom panel
+
+clear-session = Clear Session
+today-hours = Today: { $hours } / { $expected-hours }
+balance = Balance: { $hours }
+work-hours = Work Hours
+pomodoro = Pomodoro
+pomodoro-work = Work:
+pomodoro-break = Break:
+pomodoro-work-left = { $tag }: { $time } left
+pomodoro-break-left = Break: { $time } left
+monday = Monday
+tuesday = Tuesday
+wednesday = Wednesday
+thursday = Thursday
+friday = Friday
+saturday = Saturday
+sunday = Sunday
+
+## Pomodoro
+
+completed-pomodoros = Pomodoros completed today
+pomodoro-completed = Pomodoro on { $tag } completed
+break-started = Time for a { $minutes } minute break
+break-over = Break over
+break-over-body = Start a tag for the next pomodoro
+
+## Breaks
+
+break-time = Breaks: { $hours }
+start-break = Start Break
+end-break = End Break
+break-rules = Break Rules
+break-rule-after = More than
+break-rule-at-least = worked needs a break of at least
+add-break-rule = Add Rule
+minimum-break = Breaks count from:
+max-hours-without-break = At most without a break:
+hours-suffix = { " " }h
+break-too-short = More than { $hours } hours worked needs a { $required-minutes } minute break, { $taken-minutes } taken
+break-too-late = Worked more than { $hours } hours without a break
+
+## Reminders
+
+reminders = Reminders
+auto-stop-at = Stop running tags at
+long-run-after = Notify when a tag runs longer than
+day-end-reminder-at = Remind to review the day at
+auto-stopped = Tags stopped automatically
+long-run = { $tag } is still running
+long-run-body = It has been running for more than { $hours } hours
+day-end-reminder = Time to wrap up the day
+day-end-reminder-body = Review today's time segments and clear the session
+
+## Reports
+
+rounded-hours = Rounded Hours
+include-subtags = Include Subtags
+export-csv = Export CSV
+report-exported = Report exported
+saved-to = Saved to { $path }
+period = Period
+total = Total
+earnings = Earnings
+billable-hours = Billable Hours
+non-billable-hours = Non-billable Hours
+earnings-in-currency = Earnings ({ $currency })
+
+## Charts
+
+today = Today
+hours-per-day-weeks = Hours Per Day, Weeks:
+
+## Invoices
+
+invoices-need-billable-tag = Mark a tag as billable in its settings to create invoices.
+invoice-client = Client
+invoice-from = From
+invoice-to = To
+line-items = Line Items
+per-segment = Per Segment
+per-day = Per Day
+enter-invoice-period = Enter the invoice period as { $format } dates.
+invoice-summary = Invoice { $number }: { $line-count ->
+    [one] 1 line item
+   *[other] { $line-count } line items
+}, { $hours } hours
+amount-due = Amount Due: { $amount } { $currency }
+generate-invoice = Generate Invoice
+invoice-created = Invoice { $number } created
+invoice-saved-to = Saved to { $html-path } and { $pdf-path }
+
+## Archived tags
+
+no-archived-tags = No archived tags.
+archived-hours = { $hours } hours
+confirm-purge = Delete the tag and all its history?
+purge = Purge
+cancel = Cancel
+unarchive = Unarchive
+
+## Idle time
+
+idle-time = Idle Time
+idle-interval = You were away from { $start } to { $end } ({ $hours }) with a timer running.
+keep-idle-time = Keep
+discard-idle-time = Discard
+reassign-idle-time = Reassign
+
+## Command palette
+
+command-palette = Command Palette
+command-palette-hint = Type a tag or an action
+no-matches = No matches
+command-start-tag = Start { $tag }
+command-stop-tag = Stop { $tag }
+command-stop-all = Stop All Tags
+command-new-tag = New Tag
+command-filter = Filter Tags
+command-open-reports = Open Reports
+command-open-charts = Open Charts
+command-open-invoices = Open Invoices
+command-open-archive = Open Archived Tags
+command-open-work-hours = Open Work Hours
+command-toggle-dark-mode = Toggle Dark Mode
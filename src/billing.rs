@@ -0,0 +1,68 @@
+use serde::{Serialize, Deserialize};
+
+// How billed hours of a tag are rounded, Global follows the minute rounding of the top panel
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BillingRounding {
+    #[default]
+    Global,
+    Off,
+    Own,
+}
+
+impl BillingRounding {
+    pub fn label(self) -> &'static str {
+        match self {
+            BillingRounding::Global => "billing-rounding-global",
+            BillingRounding::Off => "billing-rounding-off",
+            BillingRounding::Own => "billing-rounding-own",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Billing {
+    pub is_billable: bool,
+    pub hourly_rate: f64,
+    pub currency: String,
+    #[serde(default)]
+    pub client: String,
+    #[serde(default)]
+    pub rounding: BillingRounding,
+    #[serde(default = "default_rounding_scale")]
+    pub own_rounding_scale: f32,
+}
+
+impl Default for Billing {
+    fn default() -> Billing {
+        Billing {
+            is_billable: false,
+            hourly_rate: 0.0,
+            currency: "USD".to_owned(),
+            client: String::new(),
+            rounding: BillingRounding::Global,
+            own_rounding_scale: default_rounding_scale(),
+        }
+    }
+}
+
+impl Billing {
+    pub fn earnings(&self, hours: f64) -> f64 {
+        if self.is_billable {
+            hours * self.hourly_rate
+        } else {
+            0.0
+        }
+    }
+
+    pub fn rounding_scale(&self, global_rounding_scale: Option<f32>) -> Option<f32> {
+        match self.rounding {
+            BillingRounding::Global => global_rounding_scale,
+            BillingRounding::Off => None,
+            BillingRounding::Own => Some(self.own_rounding_scale),
+        }
+    }
+}
+
+fn default_rounding_scale() -> f32 {
+    0.25
+}
@@ -0,0 +1,63 @@
+use serde::{Serialize, Deserialize};
+use time::{Date, Duration};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetPeriod {
+    Daily,
+    Weekly,
+}
+
+impl BudgetPeriod {
+    pub fn first_day(self, today: Date) -> Date {
+        match self {
+            BudgetPeriod::Daily => today,
+            BudgetPeriod::Weekly => today - Duration::days(i64::from(today.weekday().number_days_from_monday())),
+        }
+    }
+
+    // Message id of the label
+    pub fn label(self) -> &'static str {
+        match self {
+            BudgetPeriod::Daily => "period-daily",
+            BudgetPeriod::Weekly => "period-weekly",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetKind {
+    Target,
+    Cap,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Budget {
+    pub hours: f64,
+    pub period: BudgetPeriod,
+    pub kind: BudgetKind,
+    pub is_notification_on: bool,
+}
+
+impl Budget {
+    pub fn new(period: BudgetPeriod) -> Budget {
+        Budget {
+            hours: 8.0,
+            period,
+            kind: BudgetKind::Target,
+            is_notification_on: true,
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn progress(&self, hours: f64) -> f32 {
+        if self.hours > 0.0 {
+            (hours / self.hours) as f32
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_exceeded(&self, hours: f64) -> bool {
+        hours > self.hours
+    }
+}
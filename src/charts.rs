@@ -0,0 +1,105 @@
+use eframe::egui::Color32;
+use eframe::egui::plot::{Bar, BarChart};
+use time::{Date, Duration, OffsetDateTime};
+use crate::tag::*;
+
+const TAG_COLORS: [Color32; 8] = [
+    Color32::from_rgb(66, 135, 245),
+    Color32::from_rgb(245, 152, 66),
+    Color32::from_rgb(87, 191, 99),
+    Color32::from_rgb(224, 76, 76),
+    Color32::from_rgb(160, 105, 214),
+    Color32::from_rgb(140, 99, 84),
+    Color32::from_rgb(227, 119, 194),
+    Color32::from_rgb(120, 180, 190),
+];
+
+// Tags without a picked color get one from the palette based on their id so it stays the same when tags are reordered
+pub fn tag_color(tag: &Tag) -> Color32 {
+    match tag.color {
+        Some([red, green, blue]) => Color32::from_rgb(red, green, blue),
+        None => TAG_COLORS[tag.id as usize % TAG_COLORS.len()],
+    }
+}
+
+fn hour_of_day(time_stamp: OffsetDateTime) -> f64 {
+    let (hour, minute, second) = time_stamp.to_hms();
+    f64::from(hour) + f64::from(minute) / 60.0 + f64::from(second) / 3600.0
+}
+
+// The tags given a row in the timeline, archived ones are left out so the rows line up with their labels
+pub fn timeline_tags(tags: &[Tag]) -> impl Iterator<Item = &Tag> {
+    tags.iter().filter(|tag| !tag.is_archived)
+}
+
+// One horizontal bar per segment started on the date, laid out on a row per tag with the x axis in hours of the day
+#[allow(clippy::cast_precision_loss)]
+pub fn timeline_charts(tags: &[Tag], date: Date, now: OffsetDateTime) -> Vec<BarChart> {
+    let mut charts = Vec::new();
+
+    for (row, tag) in timeline_tags(tags).enumerate() {
+        let bars: Vec<Bar> = tag.history.iter()
+            .chain(tag.time_segments.iter())
+            .filter(|segment| segment.start_time.unwrap().date() == date)
+            .map(|segment| {
+                let start_time = segment.start_time.unwrap();
+                let end_time = segment.end_time.unwrap_or(now);
+                let start_hour = hour_of_day(start_time);
+                let hours = (end_time - start_time).as_seconds_f64() / 3600.0;
+
+                Bar::new(row as f64, hours)
+                    .base_offset(start_hour)
+                    .width(0.6)
+                    .name(tag.display_name())
+            })
+            .collect();
+
+        if !bars.is_empty() {
+            charts.push(BarChart::new(bars).horizontal().color(tag_color(tag)).name(tag.display_name()));
+        }
+    }
+
+    charts
+}
+
+pub fn daily_hours(tag: &Tag, first_day: Date, day_count: usize) -> Vec<f64> {
+    let mut hours_per_day = vec![0f64; day_count];
+
+    for segment in tag.finished_segments() {
+        let days_since_first = (segment.start_time.unwrap().date() - first_day).whole_days();
+        if let Ok(day_index) = usize::try_from(days_since_first) {
+            if day_index < day_count {
+                hours_per_day[day_index] += segment.hours_total;
+            }
+        }
+    }
+
+    hours_per_day
+}
+
+// A bar per day for every tag, each tag's chart stacked on top of the ones before it
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
+pub fn daily_hours_charts(tags: &[Tag], first_day: Date, day_count: usize) -> Vec<BarChart> {
+    let mut charts: Vec<BarChart> = Vec::new();
+
+    for tag in tags {
+        let bars: Vec<Bar> = daily_hours(tag, first_day, day_count).iter()
+            .enumerate()
+            .map(|(day_index, hours)| {
+                let day = first_day + Duration::days(day_index as i64);
+                Bar::new(day_index as f64, *hours)
+                    .width(0.7)
+                    .name(format!("{} {:02}-{:02}", tag.display_name(), u8::from(day.month()), day.day()))
+            })
+            .collect();
+
+        let chart_refs: Vec<&BarChart> = charts.iter().collect();
+        let chart = BarChart::new(bars)
+            .color(tag_color(tag))
+            .name(tag.display_name())
+            .stack_on(&chart_refs);
+        charts.push(chart);
+    }
+
+    charts
+}
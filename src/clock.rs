@@ -0,0 +1,141 @@
+use serde::{Serialize, Deserialize};
+use time::{Date, Month, OffsetDateTime, Time};
+use crate::i18n::system_locale;
+use crate::time_segment::parse_date;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum HourFormat {
+    TwentyFourHour,
+    TwelveHour,
+}
+
+impl HourFormat {
+    // Message id of the label
+    pub fn label(self) -> &'static str {
+        match self {
+            HourFormat::TwentyFourHour => "hour-format-24",
+            HourFormat::TwelveHour => "hour-format-12",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    YearMonthDay,
+    DayMonthYearDots,
+    DayMonthYearSlashes,
+    MonthDayYear,
+}
+
+impl DateFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            DateFormat::YearMonthDay => "YYYY-MM-DD",
+            DateFormat::DayMonthYearDots => "DD.MM.YYYY",
+            DateFormat::DayMonthYearSlashes => "DD/MM/YYYY",
+            DateFormat::MonthDayYear => "MM/DD/YYYY",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ClockFormat {
+    pub hour_format: HourFormat,
+    pub date_format: DateFormat,
+}
+
+impl Default for ClockFormat {
+    fn default() -> ClockFormat {
+        ClockFormat::from_locale(&system_locale("LC_TIME"))
+    }
+}
+
+impl ClockFormat {
+    // Picks the formats from the territory of a POSIX locale such as "en_US.UTF-8", falling back to ISO dates and a
+    // 24-hour clock
+    pub fn from_locale(locale: &str) -> ClockFormat {
+        let territory = locale.split(['.', '@']).next().unwrap_or_default()
+            .split('_').nth(1).unwrap_or_default()
+            .to_uppercase();
+
+        let hour_format = match territory.as_str() {
+            "US" | "CA" | "AU" | "NZ" | "IN" | "PH" | "PK" | "EG" => HourFormat::TwelveHour,
+            _ => HourFormat::TwentyFourHour,
+        };
+        let date_format = match territory.as_str() {
+            "US" | "PH" => DateFormat::MonthDayYear,
+            "DE" | "AT" | "CH" | "RU" | "PL" | "CZ" | "SK" | "FI" | "NO" | "DK" | "UA" | "TR" => DateFormat::DayMonthYearDots,
+            "GB" | "IE" | "FR" | "ES" | "IT" | "PT" | "BR" | "AU" | "NZ" | "IN" | "BE" | "NL" | "GR" | "MX" | "AR" => DateFormat::DayMonthYearSlashes,
+            _ => DateFormat::YearMonthDay,
+        };
+
+        ClockFormat { hour_format, date_format }
+    }
+
+    pub fn format_time(self, time_stamp: OffsetDateTime) -> String {
+        let (hour, minute) = (time_stamp.hour(), time_stamp.minute());
+
+        match self.hour_format {
+            HourFormat::TwentyFourHour => format!("{hour:02}:{minute:02}"),
+            HourFormat::TwelveHour => {
+                let period = if hour < 12 { "AM" } else { "PM" };
+                let twelve_hour = if hour % 12 == 0 { 12 } else { hour % 12 };
+                format!("{twelve_hour}:{minute:02} {period}")
+            },
+        }
+    }
+
+    // Accepts either clock regardless of the preference, like "9:05", "21:05" or "9:05 pm", with optional minutes
+    #[allow(clippy::unused_self)]
+    pub fn parse_time(self, time_text: &str) -> Option<Time> {
+        let time_text = time_text.trim().to_lowercase();
+        let (time_text, period) = if let Some(time_text) = time_text.strip_suffix("am") {
+            (time_text.trim_end(), Some(false))
+        } else if let Some(time_text) = time_text.strip_suffix("pm") {
+            (time_text.trim_end(), Some(true))
+        } else {
+            (time_text.as_str(), None)
+        };
+
+        let mut parts = time_text.splitn(2, ':');
+        let mut hour = parts.next()?.trim().parse::<u8>().ok()?;
+        let minute = match parts.next() {
+            Some(minute_text) => minute_text.trim().parse::<u8>().ok()?,
+            None => 0,
+        };
+
+        if let Some(is_afternoon) = period {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            hour = hour % 12 + if is_afternoon { 12 } else { 0 };
+        }
+
+        Time::from_hms(hour, minute, 0).ok()
+    }
+
+    pub fn format_date(self, date: Date) -> String {
+        let (year, month, day) = (date.year(), u8::from(date.month()), date.day());
+
+        match self.date_format {
+            DateFormat::YearMonthDay => format!("{year}-{month:02}-{day:02}"),
+            DateFormat::DayMonthYearDots => format!("{day:02}.{month:02}.{year}"),
+            DateFormat::DayMonthYearSlashes => format!("{day:02}/{month:02}/{year}"),
+            DateFormat::MonthDayYear => format!("{month:02}/{day:02}/{year}"),
+        }
+    }
+
+    // ISO dates are understood whatever the preference
+    pub fn parse_date(self, date_text: &str) -> Option<Date> {
+        let date_text = date_text.trim();
+        let parts: Vec<&str> = date_text.split(['.', '/']).collect();
+
+        let ((DateFormat::DayMonthYearDots | DateFormat::DayMonthYearSlashes, [day, month, year])
+            | (DateFormat::MonthDayYear, [month, day, year])) = (self.date_format, parts.as_slice()) else {
+            return parse_date(date_text);
+        };
+
+        let month = Month::try_from(month.trim().parse::<u8>().ok()?).ok()?;
+        Date::from_calendar_date(year.trim().parse::<i32>().ok()?, month, day.trim().parse::<u8>().ok()?).ok()
+    }
+}
@@ -0,0 +1,87 @@
+use crate::i18n::*;
+use crate::tag::*;
+use crate::tag_tree::*;
+
+pub const PALETTE_RESULT_COUNT: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    ToggleTag(u32),
+    StopAll,
+    NewTag,
+    Filter,
+    OpenReports,
+    OpenCharts,
+    OpenInvoices,
+    OpenArchive,
+    OpenWorkHours,
+    ToggleDarkMode,
+}
+
+// The general actions with the message ids of their labels
+const ACTIONS: [(Command, &str); 9] = [
+    (Command::StopAll, "command-stop-all"),
+    (Command::NewTag, "command-new-tag"),
+    (Command::Filter, "command-filter"),
+    (Command::OpenReports, "command-open-reports"),
+    (Command::OpenCharts, "command-open-charts"),
+    (Command::OpenInvoices, "command-open-invoices"),
+    (Command::OpenArchive, "command-open-archive"),
+    (Command::OpenWorkHours, "command-open-work-hours"),
+    (Command::ToggleDarkMode, "command-toggle-dark-mode"),
+];
+
+// Every character of the query has to appear in order in the text, runs of consecutive characters and matches at
+// the start of words score higher while skipped characters cost a little
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut text_index = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for query_character in query.to_lowercase().chars().filter(|character| !character.is_whitespace()) {
+        let match_index = text_index + text[text_index..].iter().position(|&character| character == query_character)?;
+
+        score += 1;
+        if match_index == 0 || !text[match_index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        match previous_match {
+            Some(previous_index) if previous_index + 1 == match_index => score += 5,
+            Some(previous_index) => score -= i32::try_from(match_index - previous_index - 1).unwrap_or(i32::MAX).min(3),
+            None => (),
+        }
+
+        previous_match = Some(match_index);
+        text_index = match_index + 1;
+    }
+
+    Some(score)
+}
+
+// Start or stop for every tag that isn't archived followed by the general actions
+pub fn palette_entries(tags: &[Tag], translator: &Translator) -> Vec<(Command, String)> {
+    let mut entries: Vec<(Command, String)> = tag_order(tags, false, TagSort::Manual).into_iter()
+        .filter(|&(tag_index, _)| !tags[tag_index].is_archived)
+        .map(|(tag_index, _)| {
+            let message_id = if tags[tag_index].is_active_segment { "command-stop-tag" } else { "command-start-tag" };
+            let label = translator.tr_args(message_id, &[("tag", tag_path(tags, tag_index).into())]);
+            (Command::ToggleTag(tags[tag_index].id), label)
+        })
+        .collect();
+    entries.extend(ACTIONS.iter().map(|(command, message_id)| (*command, translator.tr(message_id))));
+
+    entries
+}
+
+pub fn matching_commands(tags: &[Tag], translator: &Translator, query: &str) -> Vec<(Command, String)> {
+    let mut scored_entries: Vec<(i32, Command, String)> = palette_entries(tags, translator).into_iter()
+        .filter_map(|(command, label)| fuzzy_score(query, &label).map(|score| (score, command, label)))
+        .collect();
+    scored_entries.sort_by_key(|(score, _, _)| -score);
+
+    scored_entries.into_iter()
+        .take(PALETTE_RESULT_COUNT)
+        .map(|(_, command, label)| (command, label))
+        .collect()
+}
@@ -0,0 +1,146 @@
+use serde::{Serialize, Deserialize};
+use time::{Date, Duration, OffsetDateTime};
+use crate::tag::*;
+use crate::time_segment::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct BreakRule {
+    // Working more than this many hours a day calls for at least the break minutes
+    pub worked_hours: f64,
+    pub break_minutes: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct BreakRules {
+    pub is_enabled: bool,
+    pub rules: Vec<BreakRule>,
+    // Shorter pauses don't count as a break
+    pub minimum_break_minutes: u32,
+    pub max_hours_without_break: f64,
+}
+
+// The German working hours act: 30 minutes after 6 hours, 45 after 9, in breaks of at least 15 minutes and no more
+// than 6 hours in a row
+impl Default for BreakRules {
+    fn default() -> BreakRules {
+        BreakRules {
+            is_enabled: false,
+            rules: vec![
+                BreakRule { worked_hours: 6.0, break_minutes: 30 },
+                BreakRule { worked_hours: 9.0, break_minutes: 45 },
+            ],
+            minimum_break_minutes: 15,
+            max_hours_without_break: 6.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BreakViolation {
+    TooLittleBreak { worked_hours: f64, required_minutes: u32, taken_minutes: i64 },
+    TooLongWithoutBreak { hours: f64 },
+}
+
+pub struct WorkDay {
+    pub worked_hours: f64,
+    pub break_minutes: i64,
+    pub longest_stretch_hours: f64,
+}
+
+impl WorkDay {
+    // Every time segment started on the day, the running ones up to now, minus the logged breaks. Overlapping segments
+    // of parallel tags count once, and anything between them that is long enough counts as a break whether it was
+    // logged as one or not
+    pub fn new(tags: &[Tag], breaks: &[TimeSegment], day: Date, now: OffsetDateTime, minimum_break_minutes: u32) -> WorkDay {
+        let worked_intervals = day_intervals(tags.iter().flat_map(|tag| tag.history.iter().chain(tag.time_segments.iter())), day, now);
+        let break_intervals = day_intervals(breaks.iter(), day, now);
+        let mut intervals = without_breaks(worked_intervals, &break_intervals);
+        intervals.sort_by_key(|(start_time, _)| *start_time);
+
+        let minimum_break = Duration::minutes(i64::from(minimum_break_minutes));
+        let mut worked_time = Duration::ZERO;
+        let mut break_time = Duration::ZERO;
+        let mut longest_stretch = Duration::ZERO;
+        let mut stretch: Option<(OffsetDateTime, OffsetDateTime)> = None;
+
+        for (start_time, end_time) in intervals {
+            match stretch {
+                Some((stretch_start, stretch_end)) if start_time - stretch_end < minimum_break => {
+                    worked_time += end_time - start_time.max(stretch_end).min(end_time);
+                    stretch = Some((stretch_start, stretch_end.max(end_time)));
+                },
+                _ => {
+                    if let Some((stretch_start, stretch_end)) = stretch {
+                        break_time += start_time - stretch_end;
+                        longest_stretch = longest_stretch.max(stretch_end - stretch_start);
+                    }
+                    worked_time += end_time - start_time;
+                    stretch = Some((start_time, end_time));
+                },
+            }
+        }
+        if let Some((stretch_start, stretch_end)) = stretch {
+            longest_stretch = longest_stretch.max(stretch_end - stretch_start);
+        }
+
+        WorkDay {
+            worked_hours: worked_time.as_seconds_f64() / 3600f64,
+            break_minutes: break_time.whole_minutes(),
+            longest_stretch_hours: longest_stretch.as_seconds_f64() / 3600f64,
+        }
+    }
+}
+
+fn day_intervals<'a>(segments: impl Iterator<Item = &'a TimeSegment>, day: Date, now: OffsetDateTime) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    segments.filter(|segment| segment.start_time.unwrap().date() == day)
+        .map(|segment| (segment.start_time.unwrap(), segment.end_time.unwrap_or(now)))
+        .filter(|(start_time, end_time)| end_time > start_time)
+        .collect()
+}
+
+// Cuts the logged breaks out of the intervals, splitting the ones a break falls in the middle of, e.g. a break taken
+// while a tag kept running
+fn without_breaks(intervals: Vec<(OffsetDateTime, OffsetDateTime)>, breaks: &[(OffsetDateTime, OffsetDateTime)]) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    breaks.iter().fold(intervals, |intervals, &(break_start, break_end)| {
+        intervals.into_iter()
+            .flat_map(|(start_time, end_time)| [(start_time, end_time.min(break_start)), (start_time.max(break_end), end_time)])
+            .filter(|(start_time, end_time)| end_time > start_time)
+            .collect()
+    })
+}
+
+impl BreakRules {
+    pub fn violations(&self, work_day: &WorkDay) -> Vec<BreakViolation> {
+        let mut violations = Vec::new();
+        if !self.is_enabled {
+            return violations;
+        }
+
+        // Only the strictest rule that applies is reported
+        let required_rule = self.rules.iter()
+            .filter(|rule| work_day.worked_hours > rule.worked_hours)
+            .max_by_key(|rule| rule.break_minutes);
+        if let Some(rule) = required_rule {
+            if work_day.break_minutes < i64::from(rule.break_minutes) {
+                violations.push(BreakViolation::TooLittleBreak {
+                    worked_hours: rule.worked_hours,
+                    required_minutes: rule.break_minutes,
+                    taken_minutes: work_day.break_minutes,
+                });
+            }
+        }
+
+        if self.max_hours_without_break > 0.0 && work_day.longest_stretch_hours > self.max_hours_without_break {
+            violations.push(BreakViolation::TooLongWithoutBreak { hours: self.max_hours_without_break });
+        }
+
+        violations
+    }
+}
+
+pub fn break_hours_on(breaks: &[TimeSegment], day: Date, now: OffsetDateTime) -> f64 {
+    breaks.iter()
+        .filter(|segment| segment.start_time.unwrap().date() == day)
+        .map(|segment| segment.elapsed_hours(now))
+        .sum()
+}
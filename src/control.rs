@@ -0,0 +1,178 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use crate::tag::*;
+use crate::tag_tree::*;
+
+const CONTROL_SOCKET_NAME: &str = "daily-time-keeper.sock";
+const CONTROL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+const ERROR_PREFIX: &str = "error: ";
+const REQUEST_USAGE: &str = "expected list, status, start <tag>, stop [<tag>] or add <date> <start> <end> <tag>";
+
+// In the user's runtime directory where there is one so other users can't reach it
+pub fn control_socket_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map_or_else(std::env::temp_dir, PathBuf::from)
+        .join(CONTROL_SOCKET_NAME)
+}
+
+// One request per line with the arguments separated by tabs, which is what `ctl` sends so times like "9:05 pm" and tag
+// names with spaces stay whole. Typed by hand they may be separated by spaces instead, the tag taking up the rest of the
+// line. Tags are given by id, name or path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlRequest {
+    List,
+    Status,
+    Start(String),
+    // Stops every running tag without one
+    Stop(Option<String>),
+    AddSegment { date: String, start_time: String, end_time: String, tag: String },
+}
+
+impl ControlRequest {
+    pub fn parse(line: &str) -> Result<ControlRequest, String> {
+        let line = line.trim_matches(['\n', '\r', ' ']);
+        let arguments: Vec<&str> = if line.contains('\t') {
+            line.split('\t').map(str::trim).filter(|argument| !argument.is_empty()).collect()
+        } else {
+            let (name, rest) = line.split_once(' ').map_or((line, ""), |(name, rest)| (name, rest.trim()));
+            // The date and times of `add` come ahead of the tag
+            let mut arguments = vec![name];
+            let mut rest = rest;
+            while name == "add" && arguments.len() < 4 && !rest.is_empty() {
+                let (argument, remainder) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                arguments.push(argument);
+                rest = remainder.trim_start();
+            }
+            if !rest.is_empty() {
+                arguments.push(rest);
+            }
+            arguments
+        };
+
+        match arguments.as_slice() {
+            ["list"] => Ok(ControlRequest::List),
+            ["status"] => Ok(ControlRequest::Status),
+            ["start", tag @ ..] if !tag.is_empty() => Ok(ControlRequest::Start(tag.join(" "))),
+            ["stop"] => Ok(ControlRequest::Stop(None)),
+            ["stop", tag @ ..] => Ok(ControlRequest::Stop(Some(tag.join(" ")))),
+            ["add", arguments @ ..] => match arguments {
+                [date, start_time, end_time, tag @ ..] if !tag.is_empty() => Ok(ControlRequest::AddSegment {
+                    date: (*date).to_owned(),
+                    start_time: (*start_time).to_owned(),
+                    end_time: (*end_time).to_owned(),
+                    tag: tag.join(" "),
+                }),
+                _ => Err(format!("add needs a date, a start and end time and a tag, {REQUEST_USAGE}")),
+            },
+            _ => Err(format!("unknown request \"{}\", {REQUEST_USAGE}", line.replace('\t', " "))),
+        }
+    }
+
+    pub fn is_modifying(&self) -> bool {
+        !matches!(self, ControlRequest::List | ControlRequest::Status)
+    }
+}
+
+// Tags that aren't archived by id, path or name, ignoring case
+pub fn find_tag(tags: &[Tag], text: &str) -> Option<usize> {
+    let text = text.trim();
+    let lowercase_text = text.to_lowercase();
+
+    (0..tags.len())
+        .filter(|&tag_index| !tags[tag_index].is_archived)
+        .find(|&tag_index| {
+            text.parse::<u32>().is_ok_and(|tag_id| tags[tag_index].id == tag_id)
+                || tags[tag_index].name.to_lowercase() == lowercase_text
+                || tag_path(tags, tag_index).to_lowercase() == lowercase_text
+        })
+}
+
+pub struct ControlCall {
+    pub request: Result<ControlRequest, String>,
+    stream: UnixStream,
+}
+
+impl ControlCall {
+    pub fn reply(mut self, reply: Result<String, String>) {
+        let text = match reply {
+            Ok(text) => text,
+            Err(message) => format!("{ERROR_PREFIX}{message}\n"),
+        };
+        if let Err(error_message) = self.stream.write_all(text.as_bytes()) {
+            println!("Unable to answer a control request: {error_message}");
+        }
+    }
+}
+
+// Accepts connections on a thread of its own and hands the requests to the event loop, calling `wake` so it gets to
+// them without waiting for the next repaint
+pub struct ControlServer {
+    path: PathBuf,
+    calls: Receiver<ControlCall>,
+}
+
+impl ControlServer {
+    pub fn start(path: &Path, wake: impl Fn() + Send + 'static) -> io::Result<ControlServer> {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(io::ErrorKind::AddrInUse, "another instance is already listening"));
+        }
+        // Left behind by an instance that didn't shut down cleanly
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        let (sender, calls) = channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().filter_map(Result::ok) {
+                let mut line = String::new();
+                stream.set_read_timeout(Some(CONTROL_TIMEOUT)).ok();
+                stream.set_write_timeout(Some(CONTROL_TIMEOUT)).ok();
+                if BufReader::new(&stream).read_line(&mut line).is_err() {
+                    continue;
+                }
+
+                if sender.send(ControlCall { request: ControlRequest::parse(&line), stream }).is_err() {
+                    break;
+                }
+                wake();
+            }
+        });
+
+        Ok(ControlServer { path: path.to_owned(), calls })
+    }
+
+    pub fn next_call(&self) -> Option<ControlCall> {
+        self.calls.try_recv().ok()
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+// The arguments of `ctl` as a request line
+pub fn request_line(arguments: &[String]) -> String {
+    arguments.join("\t")
+}
+
+// The reply of the running instance, Err inside when it turned the request down
+pub fn send_request(path: &Path, request: &str) -> io::Result<Result<String, String>> {
+    let mut stream = UnixStream::connect(path)?;
+    writeln!(stream, "{request}")?;
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+
+    Ok(match reply.strip_prefix(ERROR_PREFIX) {
+        Some(message) => Err(message.trim_end().to_owned()),
+        None => Ok(reply),
+    })
+}
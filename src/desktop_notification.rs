@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use zbus::blocking::Connection;
+use zbus::blocking::fdo::DBusProxy;
+use zbus::names::BusName;
+use zbus::zvariant::Value;
+
+pub const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
+pub const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+const NOTIFICATION_APP_NAME: &str = "daily-time-keeper";
+
+// Sends notifications to the desktop over D-Bus following the freedesktop notification spec
+pub struct DesktopNotifier {
+    connection: Connection,
+}
+
+impl DesktopNotifier {
+    pub fn connect() -> Option<DesktopNotifier> {
+        DesktopNotifier::with_connection(Connection::session().ok()?)
+    }
+
+    // None unless a notification daemon is running on the bus or can be started by it
+    pub fn with_connection(connection: Connection) -> Option<DesktopNotifier> {
+        let dbus = DBusProxy::new(&connection).ok()?;
+        let is_running = dbus.name_has_owner(BusName::try_from(NOTIFICATIONS_NAME).ok()?).unwrap_or(false);
+        let is_activatable = dbus.list_activatable_names()
+            .is_ok_and(|names| names.iter().any(|name| name.as_str() == NOTIFICATIONS_NAME));
+
+        (is_running || is_activatable).then_some(DesktopNotifier { connection })
+    }
+
+    pub fn send(&self, summary: &str, body: &str) -> bool {
+        let actions: Vec<&str> = Vec::new();
+        let hints: HashMap<&str, Value> = HashMap::new();
+        // No id to replace, no icon and the daemon's default timeout
+        let arguments = (NOTIFICATION_APP_NAME, 0u32, "", summary, body, actions, hints, -1i32);
+
+        self.connection.call_method(Some(NOTIFICATIONS_NAME), NOTIFICATIONS_PATH, Some(NOTIFICATIONS_NAME), "Notify", &arguments)
+            .is_ok()
+    }
+}
+
+// Connects once and sends from a thread of its own, so a slow or missing notification daemon can't hold up the window.
+// Each notification is answered with whether it was delivered
+pub struct DesktopNotifierThread {
+    notifications: Sender<(String, String)>,
+    replies: Receiver<Result<(), (String, String)>>,
+    pending_count: usize,
+}
+
+impl DesktopNotifierThread {
+    pub fn start(connect: impl FnOnce() -> Option<DesktopNotifier> + Send + 'static) -> DesktopNotifierThread {
+        let (notifications, notification_receiver) = channel::<(String, String)>();
+        let (reply_sender, replies) = channel();
+        thread::spawn(move || {
+            let desktop_notifier = connect();
+            for (summary, body) in notification_receiver {
+                let is_sent = desktop_notifier.as_ref().is_some_and(|desktop_notifier| desktop_notifier.send(&summary, &body));
+                if reply_sender.send(if is_sent { Ok(()) } else { Err((summary, body)) }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        DesktopNotifierThread { notifications, replies, pending_count: 0 }
+    }
+
+    // False when the thread is gone and the notification has to be shown some other way
+    pub fn send(&mut self, summary: &str, body: &str) -> bool {
+        let is_queued = self.notifications.send((summary.to_owned(), body.to_owned())).is_ok();
+        if is_queued {
+            self.pending_count += 1;
+        }
+        is_queued
+    }
+
+    // The notifications answered since the last call that the daemon didn't take
+    pub fn undelivered(&mut self) -> Vec<(String, String)> {
+        let replies: Vec<Result<(), (String, String)>> = self.replies.try_iter().collect();
+        self.pending_count = self.pending_count.saturating_sub(replies.len());
+
+        replies.into_iter().filter_map(Result::err).collect()
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.pending_count > 0
+    }
+}
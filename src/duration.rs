@@ -0,0 +1,66 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DurationFormat {
+    Decimal,
+    HoursMinutes,
+    HoursMinutesSeconds,
+}
+
+impl DurationFormat {
+    // Message id of the label
+    pub fn label(self) -> &'static str {
+        match self {
+            DurationFormat::Decimal => "duration-decimal",
+            DurationFormat::HoursMinutes => "duration-hours-minutes",
+            DurationFormat::HoursMinutesSeconds => "duration-hours-minutes-seconds",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct DurationDisplay {
+    pub format: DurationFormat,
+    // Decimal places, only used by the decimal format
+    pub precision: u8,
+}
+
+impl Default for DurationDisplay {
+    fn default() -> DurationDisplay {
+        DurationDisplay {
+            format: DurationFormat::Decimal,
+            precision: 2,
+        }
+    }
+}
+
+impl DurationDisplay {
+    // How often a running duration has to be redrawn for its last digit to stay current, between a second and a minute
+    pub fn refresh_interval(self) -> std::time::Duration {
+        let seconds = match self.format {
+            DurationFormat::Decimal => 3600. / 10f64.powi(i32::from(self.precision)),
+            DurationFormat::HoursMinutes => 60.,
+            DurationFormat::HoursMinutesSeconds => 1.,
+        };
+
+        std::time::Duration::from_secs_f64(seconds.clamp(1., 60.))
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn format(self, hours: f64) -> String {
+        let sign = if hours < 0. { "-" } else { "" };
+        let hours = hours.abs();
+
+        match self.format {
+            DurationFormat::Decimal => format!("{sign}{hours:.precision$}", precision = usize::from(self.precision)),
+            DurationFormat::HoursMinutes => {
+                let minutes = (hours * 60.).round() as i64;
+                format!("{sign}{}:{:02}", minutes / 60, minutes % 60)
+            },
+            DurationFormat::HoursMinutesSeconds => {
+                let seconds = (hours * 3600.).round() as i64;
+                format!("{sign}{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+            },
+        }
+    }
+}
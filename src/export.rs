@@ -0,0 +1,49 @@
+use std::fmt::Write;
+use crate::duration::*;
+use crate::report::*;
+
+pub const REPORT_EXPORT_PATH: &str = "daily-time-keeper_report.csv";
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn report_to_csv(report: &Report, duration_display: DurationDisplay) -> String {
+    let mut csv = String::new();
+
+    let header: Vec<String> = std::iter::once("Period")
+        .chain(report.tag_names.iter().map(String::as_str))
+        .chain(std::iter::once("Total"))
+        .map(csv_field)
+        .collect();
+    writeln!(csv, "{}", header.join(",")).unwrap();
+
+    for row in &report.rows {
+        let hours: Vec<String> = row.tag_hours.iter().map(|hours| duration_display.format(*hours)).collect();
+        writeln!(csv, "{},{},{}", csv_field(&row.label), hours.join(","), duration_display.format(row.total)).unwrap();
+    }
+
+    let tag_totals: Vec<String> = report.tag_totals.iter().map(|hours| duration_display.format(*hours)).collect();
+    writeln!(csv, "Total,{},{}", tag_totals.join(","), duration_display.format(report.grand_total)).unwrap();
+
+    let tag_earnings: Vec<String> = report.tag_earnings.iter()
+        .map(|earnings| match earnings {
+            Some((amount, currency)) => csv_field(&format!("{amount:.2} {currency}")),
+            None => String::new(),
+        })
+        .collect();
+    writeln!(csv, "Earnings,{},", tag_earnings.join(",")).unwrap();
+
+    writeln!(csv).unwrap();
+    writeln!(csv, "Billable Hours,{}", duration_display.format(report.billable_total)).unwrap();
+    writeln!(csv, "Non-billable Hours,{}", duration_display.format(report.non_billable_total)).unwrap();
+    for (currency, amount) in &report.earnings_per_currency {
+        writeln!(csv, "{},{amount:.2}", csv_field(&format!("Earnings ({currency})"))).unwrap();
+    }
+
+    csv
+}
@@ -0,0 +1,77 @@
+use time::Date;
+use crate::clock::*;
+use crate::tag::*;
+use crate::tag_tree::*;
+use crate::time_segment::*;
+
+pub struct TagFilter {
+    pub text: String,
+    pub first_day: Option<Date>,
+    pub last_day: Option<Date>,
+}
+
+impl TagFilter {
+    // The text is matched case-insensitively, dates that don't parse leave that end of the range open
+    pub fn new(text: &str, first_day_field: &str, last_day_field: &str, clock_format: ClockFormat) -> TagFilter {
+        TagFilter {
+            text: text.trim().to_lowercase(),
+            first_day: clock_format.parse_date(first_day_field),
+            last_day: clock_format.parse_date(last_day_field),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty() || self.first_day.is_some() || self.last_day.is_some()
+    }
+
+    fn is_in_range(&self, segment: &TimeSegment) -> bool {
+        let date = segment.start_time.unwrap().date();
+        self.first_day.is_none_or(|first_day| date >= first_day)
+            && self.last_day.is_none_or(|last_day| date <= last_day)
+    }
+
+    pub fn is_name_match(&self, tags: &[Tag], tag_index: usize) -> bool {
+        tag_path(tags, tag_index).to_lowercase().contains(&self.text)
+    }
+
+    // Every segment in the date range of a tag whose name matches, otherwise the ones whose note matches
+    pub fn matches_segment(&self, tags: &[Tag], tag_index: usize, segment: &TimeSegment) -> bool {
+        self.matches_tag_segment(self.is_name_match(tags, tag_index), segment)
+    }
+
+    // The same with the name match of the segment's tag worked out beforehand
+    pub fn matches_tag_segment(&self, is_tag_name_match: bool, segment: &TimeSegment) -> bool {
+        self.is_in_range(segment) && (is_tag_name_match || segment.note.to_lowercase().contains(&self.text))
+    }
+
+    pub fn matches_tag(&self, tags: &[Tag], tag_index: usize) -> bool {
+        let tag = &tags[tag_index];
+        let is_date_filtered = self.first_day.is_some() || self.last_day.is_some();
+
+        if self.is_name_match(tags, tag_index) && !is_date_filtered {
+            return true;
+        }
+
+        tag.history.iter()
+            .chain(tag.time_segments.iter())
+            .any(|segment| self.matches_segment(tags, tag_index, segment))
+    }
+
+    // Matching tags along with their ancestors so they are still shown in their place in the tree
+    pub fn visible_tags(&self, tags: &[Tag]) -> Vec<bool> {
+        let mut is_visible = vec![!self.is_active(); tags.len()];
+
+        if self.is_active() {
+            for tag_index in 0..tags.len() {
+                if self.matches_tag(tags, tag_index) {
+                    is_visible[tag_index] = true;
+                    for ancestor in ancestor_indices(tags, tag_index) {
+                        is_visible[ancestor] = true;
+                    }
+                }
+            }
+        }
+
+        is_visible
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt::Write;
+use std::io;
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use serde::{Serialize, Deserialize};
+use time::{Date, OffsetDateTime};
+use tiny_http::{Header, Method, Request, Response, Server};
+use crate::report::*;
+use crate::tag::*;
+use crate::tag_tree::*;
+use crate::time_segment::*;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HttpApiSettings {
+    pub is_enabled: bool,
+    pub port: u16,
+    // Clients send it as `Authorization: Bearer <token>`, generated the first time the server starts
+    pub token: String,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> HttpApiSettings {
+        HttpApiSettings {
+            is_enabled: false,
+            port: 7878,
+            token: String::new(),
+        }
+    }
+}
+
+// 128 bits from the operating system's random number generator as hex
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).unwrap();
+
+    bytes.iter().fold(String::new(), |mut token, byte| {
+        write!(token, "{byte:02x}").unwrap();
+        token
+    })
+}
+
+// Looks at every byte whatever the first difference so the time it takes doesn't give the token away
+pub fn is_token_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0, |difference, (given, expected)| difference | (given ^ expected)) == 0
+}
+
+// Query strings as browsers send them, with `+` for spaces and %XX escapes, None when an escape is broken
+pub fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut characters = text.bytes();
+
+    while let Some(byte) = characters.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [characters.next()?, characters.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            },
+            byte => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ApiRequest {
+    Tags,
+    Segments { tag_id: Option<u32>, first_day: Option<Date>, last_day: Option<Date> },
+    Report(ReportPeriod),
+    Start(u32),
+    Stop(u32),
+    StopAll,
+}
+
+impl ApiRequest {
+    // The status code and message of the error response when the request doesn't match an endpoint
+    pub fn parse(method: &Method, url: &str) -> Result<ApiRequest, (u16, String)> {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let parameters: Vec<(String, String)> = query.split('&')
+            .filter_map(|parameter| parameter.split_once('='))
+            .map(|(key, value)| percent_decode(key).zip(percent_decode(value)).ok_or_else(|| (400, format!("\"{key}={value}\" isn't valid percent-encoding"))))
+            .collect::<Result<_, _>>()?;
+        let parameter = |name: &str| parameters.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str());
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+        let request = match segments.as_slice() {
+            ["tags"] => (Method::Get, ApiRequest::Tags),
+            ["segments"] => {
+                let tag_id = parameter("tag").map(|tag_id| tag_id.parse::<u32>().map_err(|_| (400, format!("\"{tag_id}\" isn't a tag id"))))
+                    .transpose()?;
+                let [first_day, last_day] = ["from", "to"].map(|name| {
+                    parameter(name).map(|day| parse_date(day).ok_or_else(|| (400, format!("\"{day}\" isn't a YYYY-MM-DD date"))))
+                        .transpose()
+                });
+                (Method::Get, ApiRequest::Segments { tag_id, first_day: first_day?, last_day: last_day? })
+            },
+            ["reports"] => {
+                let period = match parameter("period").unwrap_or("day") {
+                    "day" => ReportPeriod::Day,
+                    "week" => ReportPeriod::Week,
+                    "month" => ReportPeriod::Month,
+                    period => return Err((400, format!("\"{period}\" isn't one of day, week or month"))),
+                };
+                (Method::Get, ApiRequest::Report(period))
+            },
+            ["tags", tag_id, action @ ("start" | "stop")] => {
+                let tag_id = tag_id.parse::<u32>().map_err(|_| (404, format!("\"{tag_id}\" isn't a tag id")))?;
+                (Method::Post, if *action == "start" { ApiRequest::Start(tag_id) } else { ApiRequest::Stop(tag_id) })
+            },
+            ["stop"] => (Method::Post, ApiRequest::StopAll),
+            _ => return Err((404, format!("there is no endpoint at {path}"))),
+        };
+
+        match request {
+            (expected_method, request) if *method == expected_method => Ok(request),
+            (expected_method, _) => Err((405, format!("{path} only answers {expected_method}"))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TagView {
+    pub id: u32,
+    pub parent_id: Option<u32>,
+    pub name: String,
+    pub path: String,
+    pub is_archived: bool,
+    pub is_running: bool,
+    // Including the running segment so far
+    pub session_hours: f64,
+}
+
+impl TagView {
+    pub fn new(tags: &[Tag], tag_index: usize, now: OffsetDateTime) -> TagView {
+        let tag = &tags[tag_index];
+        TagView {
+            id: tag.id,
+            parent_id: tag.parent_id,
+            name: tag.name.clone(),
+            path: tag_path(tags, tag_index),
+            is_archived: tag.is_archived,
+            is_running: tag.is_active_segment,
+            session_hours: tag.running_total(now),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SegmentView {
+    pub tag_id: u32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub start_time: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub end_time: Option<OffsetDateTime>,
+    pub hours: f64,
+    pub note: String,
+    pub invoice_number: Option<u32>,
+}
+
+// Past and session segments, running ones included, that started between the days
+pub fn segment_views(tags: &[Tag], tag_id: Option<u32>, first_day: Option<Date>, last_day: Option<Date>, now: OffsetDateTime) -> Vec<SegmentView> {
+    let mut segments: Vec<SegmentView> = tags.iter()
+        .filter(|tag| tag_id.is_none_or(|tag_id| tag.id == tag_id))
+        .flat_map(|tag| tag.history.iter().chain(tag.time_segments.iter()).map(move |segment| (tag.id, segment)))
+        .filter(|(_, segment)| {
+            let day = segment.start_time.unwrap().date();
+            first_day.is_none_or(|first_day| day >= first_day) && last_day.is_none_or(|last_day| day <= last_day)
+        })
+        .map(|(tag_id, segment)| SegmentView {
+            tag_id,
+            start_time: segment.start_time.unwrap(),
+            end_time: segment.end_time,
+            hours: segment.elapsed_hours(now),
+            note: segment.note.clone(),
+            invoice_number: segment.invoice_number,
+        })
+        .collect();
+    segments.sort_by_key(|segment| segment.start_time);
+
+    segments
+}
+
+#[derive(Serialize)]
+pub struct ReportRowView {
+    pub period: String,
+    pub tag_hours: Vec<f64>,
+    pub total: f64,
+}
+
+#[derive(Serialize)]
+pub struct ReportView {
+    pub tags: Vec<String>,
+    pub rows: Vec<ReportRowView>,
+    pub tag_totals: Vec<f64>,
+    pub total: f64,
+    pub billable_total: f64,
+    pub non_billable_total: f64,
+}
+
+impl ReportView {
+    pub fn new(report: Report) -> ReportView {
+        ReportView {
+            tags: report.tag_names,
+            rows: report.rows.into_iter()
+                .map(|row| ReportRowView { period: row.label, tag_hours: row.tag_hours, total: row.total })
+                .collect(),
+            tag_totals: report.tag_totals,
+            total: report.grand_total,
+            billable_total: report.billable_total,
+            non_billable_total: report.non_billable_total,
+        }
+    }
+}
+
+pub struct ApiCall {
+    pub request: ApiRequest,
+    http_request: Request,
+}
+
+impl ApiCall {
+    pub fn reply(self, reply: Result<String, (u16, String)>) {
+        let (status, body) = match reply {
+            Ok(body) => (200, body),
+            Err((status, message)) => (status, error_body(&message)),
+        };
+        respond(self.http_request, status, body);
+    }
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn respond(http_request: Request, status: u16, body: String) {
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body).with_status_code(status).with_header(content_type);
+    if let Err(error_message) = http_request.respond(response) {
+        println!("Unable to answer an HTTP API request: {error_message}");
+    }
+}
+
+// Only reachable from this machine. Requests are authorized and routed on a thread of its own, then handed to the
+// event loop like the ones of the control socket
+pub struct HttpApiServer {
+    server: Arc<Server>,
+    calls: Receiver<ApiCall>,
+}
+
+impl HttpApiServer {
+    pub fn start(port: u16, token: &str, wake: impl Fn() + Send + 'static) -> io::Result<HttpApiServer> {
+        let server = Arc::new(Server::http(("127.0.0.1", port)).map_err(io::Error::other)?);
+        let (sender, calls) = channel();
+        let token = token.to_owned();
+
+        let listener = Arc::clone(&server);
+        thread::spawn(move || {
+            for http_request in listener.incoming_requests() {
+                let is_authorized = http_request.headers().iter()
+                    .filter(|header| header.field.equiv("Authorization"))
+                    .filter_map(|header| header.value.as_str().strip_prefix("Bearer "))
+                    .any(|given_token| is_token_match(given_token, &token));
+                if !is_authorized {
+                    respond(http_request, 401, error_body("missing or wrong bearer token"));
+                    continue;
+                }
+
+                match ApiRequest::parse(http_request.method(), http_request.url()) {
+                    Ok(request) => {
+                        if sender.send(ApiCall { request, http_request }).is_err() {
+                            break;
+                        }
+                        wake();
+                    },
+                    Err((status, message)) => respond(http_request, status, error_body(&message)),
+                }
+            }
+        });
+
+        Ok(HttpApiServer { server, calls })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.server.server_addr().to_ip().unwrap().port()
+    }
+
+    pub fn next_call(&self) -> Option<ApiCall> {
+        self.calls.try_recv().ok()
+    }
+}
+
+impl Drop for HttpApiServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
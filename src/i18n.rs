@@ -0,0 +1,102 @@
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use serde::{Serialize, Deserialize};
+use unic_langid::LanguageIdentifier;
+
+pub const LANGUAGES: [Language; 2] = [Language::English, Language::German];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    // Picks the language of a POSIX locale such as "de_AT.UTF-8", falling back to English
+    pub fn from_locale(locale: &str) -> Language {
+        let language = locale.split(['_', '.', '@']).next().unwrap_or_default().to_lowercase();
+
+        match language.as_str() {
+            "de" => Language::German,
+            _ => Language::English,
+        }
+    }
+
+    // Shown untranslated so a language can be found without reading the current one
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    fn identifier(self) -> LanguageIdentifier {
+        match self {
+            Language::English => "en-US",
+            Language::German => "de-DE",
+        }.parse().unwrap()
+    }
+
+    fn catalog(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en-US.ftl"),
+            Language::German => include_str!("../locales/de-DE.ftl"),
+        }
+    }
+
+    fn bundle(self) -> FluentBundle<FluentResource> {
+        let resource = FluentResource::try_new(self.catalog().to_owned())
+            .unwrap_or_else(|_| panic!("Invalid catalog for {}", self.native_name()));
+        let mut bundle = FluentBundle::new(vec![self.identifier()]);
+        // The isolation marks around arguments show up as boxes in egui's fonts
+        bundle.set_use_isolating(false);
+        bundle.add_resource(resource).unwrap();
+
+        bundle
+    }
+}
+
+// The first of LC_ALL, LC_MESSAGES or LANG that is set, the same order the C library uses
+pub fn system_locale(category: &str) -> String {
+    ["LC_ALL", category, "LANG"].iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+}
+
+pub struct Translator {
+    bundle: FluentBundle<FluentResource>,
+    // Messages missing from a catalog are shown in English
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Translator {
+    // No language means the one of the system locale
+    pub fn new(language: Option<Language>) -> Translator {
+        let language = language.unwrap_or_else(|| Language::from_locale(&system_locale("LC_MESSAGES")));
+
+        Translator {
+            bundle: language.bundle(),
+            fallback: Language::English.bundle(),
+        }
+    }
+
+    pub fn tr(&self, id: &str) -> String {
+        self.tr_args(id, &[])
+    }
+
+    pub fn tr_args(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+
+        for bundle in [&self.bundle, &self.fallback] {
+            if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+                let mut errors = Vec::new();
+                return bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned();
+            }
+        }
+
+        id.to_owned()
+    }
+}
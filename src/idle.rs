@@ -0,0 +1,140 @@
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use time::{Duration, OffsetDateTime};
+
+// Asking the desktop spawns a process, so it is only done every so often
+const SYSTEM_IDLE_POLL_INTERVAL: Duration = Duration::seconds(30);
+// After failed queries the interval doubles up to this, e.g. while xprintidle isn't installed yet
+const SYSTEM_IDLE_MAX_RETRY_INTERVAL: Duration = Duration::minutes(30);
+
+pub struct IdleTracker {
+    last_input: OffsetDateTime,
+    // The idle time the desktop reported and when it was queried, None where it can't tell
+    system_idle: Option<(Duration, OffsetDateTime)>,
+    system_idle_failures: u32,
+    next_system_poll: OffsetDateTime,
+    // The answer of the query running on a thread of its own, along with when it was taken
+    pending_system_idle: Option<Receiver<(Option<Duration>, OffsetDateTime)>>,
+    idle_since: Option<OffsetDateTime>,
+}
+
+impl IdleTracker {
+    pub fn new(now: OffsetDateTime) -> IdleTracker {
+        IdleTracker {
+            last_input: now,
+            system_idle: None,
+            system_idle_failures: 0,
+            next_system_poll: now,
+            pending_system_idle: None,
+            idle_since: None,
+        }
+    }
+
+    pub fn record_input(&mut self, now: OffsetDateTime) {
+        self.last_input = now;
+    }
+
+    pub fn record_system_idle(&mut self, idle_time: Option<Duration>, queried_at: OffsetDateTime, now: OffsetDateTime) {
+        self.system_idle = idle_time.map(|idle_time| (idle_time, queried_at));
+        self.system_idle_failures = if idle_time.is_some() { 0 } else { self.system_idle_failures.saturating_add(1) };
+        self.next_system_poll = now + system_idle_poll_interval(self.system_idle_failures);
+    }
+
+    // The query runs off the UI thread, its answer is picked up by a later call
+    pub fn poll_system_idle(&mut self, now: OffsetDateTime) {
+        if !cfg!(target_os = "linux") {
+            return;
+        }
+
+        if let Some(pending_system_idle) = &self.pending_system_idle {
+            match pending_system_idle.try_recv() {
+                Ok((idle_time, queried_at)) => self.record_system_idle(idle_time, queried_at, now),
+                Err(TryRecvError::Disconnected) => self.record_system_idle(None, now, now),
+                Err(TryRecvError::Empty) => return,
+            }
+            self.pending_system_idle = None;
+        }
+
+        if now >= self.next_system_poll {
+            let (sender, receiver) = channel();
+            // Only compared with other instants, so the offset doesn't matter and UTC can't fail on this thread
+            thread::spawn(move || {
+                let idle_time = system_idle_time();
+                sender.send((idle_time, OffsetDateTime::now_utc())).ok()
+            });
+            self.pending_system_idle = Some(receiver);
+        }
+    }
+
+    // Input in the window always counts, the desktop's idle time also catches work in other windows
+    pub fn idle_time(&self, now: OffsetDateTime) -> Duration {
+        let window_idle_time = now - self.last_input;
+
+        match self.system_idle {
+            Some((idle_time, reported_at)) => window_idle_time.min(idle_time + (now - reported_at)),
+            None => window_idle_time,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.idle_since = None;
+    }
+
+    // Returns the idle interval once the user is back after being idle for at least the threshold
+    pub fn check(&mut self, now: OffsetDateTime, threshold: Duration) -> Option<(OffsetDateTime, OffsetDateTime)> {
+        let idle_time = self.idle_time(now);
+
+        if idle_time >= threshold {
+            self.idle_since.get_or_insert(now - idle_time);
+            None
+        } else {
+            self.idle_since.take().map(|idle_since| (idle_since, now - idle_time))
+        }
+    }
+}
+
+pub fn system_idle_poll_interval(failures: u32) -> Duration {
+    SYSTEM_IDLE_POLL_INTERVAL.saturating_mul(2i32.saturating_pow(failures)).min(SYSTEM_IDLE_MAX_RETRY_INTERVAL)
+}
+
+#[cfg(target_os = "linux")]
+fn system_idle_time() -> Option<Duration> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        mutter_idle_time()
+    } else {
+        xprintidle_idle_time()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn system_idle_time() -> Option<Duration> {
+    None
+}
+
+// X11 sessions with xprintidle installed, which prints the idle time in milliseconds
+#[cfg(target_os = "linux")]
+fn xprintidle_idle_time() -> Option<Duration> {
+    let output = std::process::Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let milliseconds = String::from_utf8_lossy(&output.stdout).trim().parse::<i64>().ok()?;
+    Some(Duration::milliseconds(milliseconds))
+}
+
+// Wayland has no common idle query, GNOME's compositor answers with something like "(uint64 12345,)"
+#[cfg(target_os = "linux")]
+fn mutter_idle_time() -> Option<Duration> {
+    let output = std::process::Command::new("gdbus")
+        .args(["call", "--session", "--dest", "org.gnome.Mutter.IdleMonitor",
+               "--object-path", "/org/gnome/Mutter/IdleMonitor/Core", "--method", "org.gnome.Mutter.IdleMonitor.GetIdletime"])
+        .output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let reply = String::from_utf8_lossy(&output.stdout);
+    let milliseconds = reply.split_whitespace().nth(1)?.trim_end_matches([',', ')']).parse::<i64>().ok()?;
+    Some(Duration::milliseconds(milliseconds))
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use time::Date;
+use crate::billing::*;
+use crate::duration::*;
+use crate::pdf::*;
+use crate::report::round_hours;
+use crate::tag::*;
+use crate::time_segment::*;
+
+// Invoices always show decimal hours whatever the display preference, so the client can check hours times rate
+const INVOICE_HOURS_DISPLAY: DurationDisplay = DurationDisplay { format: DurationFormat::Decimal, precision: 2 };
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceGrouping {
+    PerSegment,
+    PerDay,
+}
+
+pub struct InvoiceLine {
+    pub date: Date,
+    // Kept apart from the description since the PDF font has no glyphs for emoji
+    pub icon: String,
+    pub description: String,
+    pub hours: f64,
+    pub hourly_rate: f64,
+    pub amount: f64,
+    pub currency: String,
+}
+
+pub struct Invoice {
+    pub number: u32,
+    pub client: String,
+    pub first_day: Date,
+    pub last_day: Date,
+    pub lines: Vec<InvoiceLine>,
+    pub total_hours: f64,
+    pub totals_per_currency: BTreeMap<String, f64>,
+}
+
+fn is_invoiceable(billing: &Billing, segment: &TimeSegment, client: &str, first_day: Date, last_day: Date) -> bool {
+    let date = segment.start_time.unwrap().date();
+    billing.is_billable
+        && billing.client == client
+        && segment.end_time.is_some()
+        && segment.invoice_number.is_none()
+        && date >= first_day
+        && date <= last_day
+}
+
+pub fn billable_clients(tags: &[Tag]) -> Vec<String> {
+    let mut clients: Vec<String> = tags.iter()
+        .filter(|tag| tag.billing.is_billable)
+        .map(|tag| tag.billing.client.clone())
+        .collect();
+    clients.sort();
+    clients.dedup();
+
+    clients
+}
+
+// Stamps the invoice number on every segment the invoice was built from so they are not billed twice
+pub fn mark_invoiced(tags: &mut [Tag], invoice: &Invoice) {
+    for tag in tags {
+        let billing = &tag.billing;
+        for segment in tag.history.iter_mut().chain(tag.time_segments.iter_mut()) {
+            if is_invoiceable(billing, segment, &invoice.client, invoice.first_day, invoice.last_day) {
+                segment.invoice_number = Some(invoice.number);
+            }
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Invoice {
+    pub fn new(number: u32, tags: &[Tag], client: &str, first_day: Date, last_day: Date,
+               grouping: InvoiceGrouping, global_rounding_scale: Option<f32>) -> Invoice {
+        let mut lines: Vec<InvoiceLine> = Vec::new();
+
+        for tag in tags {
+            let rounding_scale = tag.billing.rounding_scale(global_rounding_scale);
+            let mut hours_per_day: BTreeMap<Date, (f64, Vec<&str>)> = BTreeMap::new();
+
+            for segment in tag.history.iter().chain(tag.time_segments.iter()) {
+                if !is_invoiceable(&tag.billing, segment, client, first_day, last_day) {
+                    continue;
+                }
+
+                let date = segment.start_time.unwrap().date();
+                let hours = round_hours(segment.hours_total, rounding_scale);
+                match grouping {
+                    InvoiceGrouping::PerSegment => {
+                        let description = if segment.note.is_empty() {
+                            tag.name.clone()
+                        } else {
+                            format!("{}: {}", tag.name, segment.note)
+                        };
+                        lines.push(InvoiceLine {
+                            date,
+                            icon: tag.icon.clone(),
+                            description,
+                            hours,
+                            hourly_rate: tag.billing.hourly_rate,
+                            amount: tag.billing.earnings(hours),
+                            currency: tag.billing.currency.clone(),
+                        });
+                    },
+                    InvoiceGrouping::PerDay => {
+                        let day = hours_per_day.entry(date).or_insert((0f64, Vec::new()));
+                        day.0 += hours;
+                        if !segment.note.is_empty() {
+                            day.1.push(&segment.note);
+                        }
+                    },
+                }
+            }
+
+            for (date, (hours, notes)) in hours_per_day {
+                let description = if notes.is_empty() {
+                    tag.name.clone()
+                } else {
+                    format!("{}: {}", tag.name, notes.join("; "))
+                };
+                lines.push(InvoiceLine {
+                    date,
+                    icon: tag.icon.clone(),
+                    description,
+                    hours,
+                    hourly_rate: tag.billing.hourly_rate,
+                    amount: tag.billing.earnings(hours),
+                    currency: tag.billing.currency.clone(),
+                });
+            }
+        }
+
+        lines.sort_by_key(|line| line.date);
+
+        let mut totals_per_currency: BTreeMap<String, f64> = BTreeMap::new();
+        for line in &lines {
+            *totals_per_currency.entry(line.currency.clone()).or_insert(0f64) += line.amount;
+        }
+
+        Invoice {
+            number,
+            client: client.to_string(),
+            first_day,
+            last_day,
+            total_hours: lines.iter().map(|line| line.hours).sum(),
+            lines,
+            totals_per_currency,
+        }
+    }
+
+    pub fn file_stem(&self) -> String {
+        format!("invoice-{:05}", self.number)
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+
+        writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(html, "<title>Invoice {}</title>", self.number).unwrap();
+        writeln!(html, "<style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+                        th, td {{ padding: 4px 12px; border-bottom: 1px solid #ccc; }} .number {{ text-align: right; }}</style>").unwrap();
+        writeln!(html, "</head>\n<body>").unwrap();
+        writeln!(html, "<h1>Invoice {}</h1>", self.number).unwrap();
+        writeln!(html, "<p>Client: {}</p>", escape_html(&self.client)).unwrap();
+        writeln!(html, "<p>Period: {} to {}</p>", format_date(self.first_day), format_date(self.last_day)).unwrap();
+        writeln!(html, "<table>\n<tr><th>Date</th><th>Description</th><th>Hours</th><th>Rate</th><th>Amount</th></tr>").unwrap();
+        for line in &self.lines {
+            writeln!(html, "<tr><td>{}</td><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{:.2}</td>\
+                            <td class=\"number\">{:.2} {}</td></tr>",
+                     format_date(line.date), escape_html(format!("{} {}", line.icon, line.description).trim_start()),
+                     INVOICE_HOURS_DISPLAY.format(line.hours), line.hourly_rate,
+                     line.amount, escape_html(&line.currency)).unwrap();
+        }
+        writeln!(html, "<tr><th>Total</th><th></th><th class=\"number\">{}</th><th></th><th></th></tr>",
+                 INVOICE_HOURS_DISPLAY.format(self.total_hours)).unwrap();
+        for (currency, amount) in &self.totals_per_currency {
+            writeln!(html, "<tr><th>Amount Due</th><th></th><th></th><th></th><th class=\"number\">{amount:.2} {}</th></tr>",
+                     escape_html(currency)).unwrap();
+        }
+        writeln!(html, "</table>\n</body>\n</html>").unwrap();
+
+        html
+    }
+
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut lines = vec![
+            format!("Invoice {}", self.number),
+            String::new(),
+            format!("Client: {}", self.client),
+            format!("Period: {} to {}", format_date(self.first_day), format_date(self.last_day)),
+            String::new(),
+            format!("{:<10}  {:<40}  {:>6}  {:>8}  {:>14}", "Date", "Description", "Hours", "Rate", "Amount"),
+        ];
+
+        for line in &self.lines {
+            let description: String = line.description.chars().take(40).collect();
+            lines.push(format!("{:<10}  {description:<40}  {:>6}  {:>8.2}  {:>14}",
+                               format_date(line.date), INVOICE_HOURS_DISPLAY.format(line.hours), line.hourly_rate,
+                               format!("{:.2} {}", line.amount, line.currency)));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("{:<10}  {:<40}  {:>6}", "Total", "", INVOICE_HOURS_DISPLAY.format(self.total_hours)));
+        for (currency, amount) in &self.totals_per_currency {
+            lines.push(format!("Amount Due: {amount:.2} {currency}"));
+        }
+
+        text_to_pdf(&lines)
+    }
+}
@@ -1,428 +1,2412 @@
 #![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::wildcard_imports)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use std::fs::File;
+use std::fmt::Write as _;
 use std::io::{Read, Write};
-use std::path::Path;
-use eframe::egui::{CentralPanel, Context, ScrollArea, Button, TopBottomPanel, TextEdit, Key, Visuals};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use eframe::egui::{CentralPanel, Context, ScrollArea, Button, TopBottomPanel, TextEdit, Key, Visuals, Grid, DragValue, ProgressBar, Color32, ComboBox, Ui, Label, Sense, Rect, RichText, Id, Modifiers, Align};
+use eframe::egui::plot::{Plot, Legend};
 use eframe::{App, egui, Frame};
 use eframe::{NativeOptions, run_native};
-use time::{Time};
+use time::{Date, Duration, OffsetDateTime, Time};
 use serde::{Serialize, Deserialize};
 
+mod billing;
+mod budget;
+mod command;
+mod compliance;
+#[cfg(unix)]
+mod control;
+#[cfg(feature = "desktop-notifications")]
+mod desktop_notification;
+mod charts;
+mod clock;
+mod duration;
+mod export;
+mod filter;
+#[cfg(feature = "http-api")]
+mod http_api;
+mod i18n;
+mod idle;
+mod invoice;
+mod notification;
+mod pdf;
+mod pomodoro;
+mod reminder;
+mod report;
+mod status;
 mod tag;
+mod tag_tree;
 mod time_segment;
-
+mod workday;
+
+use billing::*;
+use budget::*;
+use command::*;
+use compliance::*;
+#[cfg(unix)]
+use control::*;
+use charts::*;
+use clock::*;
+use duration::*;
+use export::*;
+use filter::*;
+#[cfg(feature = "http-api")]
+use http_api::*;
+use i18n::*;
+use idle::*;
+use invoice::*;
+use notification::*;
+use pomodoro::*;
+use reminder::*;
+use report::*;
+use status::*;
 use tag::*;
+use tag_tree::*;
 use time_segment::*;
+use workday::*;
 
-const APP_NAME : &str = "Daily Time Keeper";
+const SAVE_FILE_NAME: &str = "daily-time-keeper_save_data.toml";
+const BUDGET_EXCEEDED_COLOR: Color32 = Color32::from_rgb(224, 76, 76);
+const BUDGET_REACHED_COLOR: Color32 = Color32::from_rgb(87, 191, 99);
+const FILTER_FIELD_ID: &str = "filter_field";
+const NEW_TAG_FIELD_ID: &str = "new_tag_field";
+const TAG_NAME_SUGGESTION_COUNT: usize = 5;
+const TIME_FIELD_WIDTH: f32 = 70.;
+const TAG_SHORTCUT_KEYS: [Key; 9] = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9];
 
 pub fn main() {
-    let path = Path::new("daily-time-keeper_save_data.toml");
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    match arguments.first().map(String::as_str) {
+        Some("ctl") => std::process::exit(run_control_client(&arguments[1..])),
+        Some("status") => std::process::exit(run_status(&arguments[1..])),
+        _ => (),
+    }
+
+    let path = save_file_path();
+    // Older versions kept the save file in the working directory
+    if !path.exists() && Path::new(SAVE_FILE_NAME).exists() {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).ok();
+        }
+        if let Err(error_message) = std::fs::copy(SAVE_FILE_NAME, &path) {
+            println!("Unable to copy {SAVE_FILE_NAME} to {}: {error_message}", path.display());
+        }
+    }
     let saved_time_manager_data = if path.exists() {
-       Some(load_from_file())
+       Some(load_from_file(&path))
     } else {
         None
     };
 
-    let app = match saved_time_manager_data {
+    let mut app = match saved_time_manager_data {
         None => TimeManager::new(),
-        Some(save_data) => {
-            TimeManager::new_from_serialized(save_data.tag_names, save_data.minute_rounding_scale, save_data.is_rounding_on, save_data.is_dark_mode)
-        },
+        Some(save_data) => TimeManager::new_from_serialized(&save_data),
     };
 
+    let app_name = app.translator.tr("app-name");
     let window_options = NativeOptions::default();
-    run_native(APP_NAME, window_options, Box::new(|_cc| Box::new(app)));
+    run_native(&app_name, window_options, Box::new(|cc| {
+        app.start_control_server(&cc.egui_ctx);
+        #[cfg(feature = "http-api")]
+        app.restart_http_api(&cc.egui_ctx);
+
+        Box::new(app)
+    }));
 }
 
 #[derive(Serialize, Deserialize)]
 struct SerializedTimeManager {
+    // Save files from before time segments were persisted only list the tag names
+    #[serde(default, skip_serializing)]
     tag_names: Vec<String>,
     minute_rounding_scale: f32,
     is_rounding_on: bool,
     is_dark_mode: bool,
-}
-
-impl SerializedTimeManager {
-    fn new(tag_names: Vec<String>, minute_rounding_scale: f32, is_rounding_on: bool, is_dark_mode: bool) -> SerializedTimeManager {
-        SerializedTimeManager {
-            tag_names,
-            minute_rounding_scale,
-            is_rounding_on,
-            is_dark_mode,
-        }
-    }
+    #[serde(default = "first_invoice_number")]
+    next_invoice_number: u32,
+    #[serde(default)]
+    work_schedule: WorkSchedule,
+    #[serde(default)]
+    break_rules: BreakRules,
+    #[serde(default)]
+    reminders: ReminderSettings,
+    #[serde(default)]
+    tag_sort: TagSort,
+    #[serde(default)]
+    duration_display: DurationDisplay,
+    #[serde(default)]
+    clock_format: ClockFormat,
+    // Zero turns idle detection off
+    #[serde(default)]
+    idle_minutes: u32,
+    #[serde(default)]
+    pomodoro: PomodoroSettings,
+    // None follows the system locale
+    #[serde(default)]
+    language: Option<Language>,
+    #[cfg(feature = "http-api")]
+    #[serde(default)]
+    http_api: HttpApiSettings,
+    #[serde(default)]
+    tags: Vec<SerializedTag>,
+    #[serde(default)]
+    breaks: Vec<SerializedTimeSegment>,
 }
 
 // #[derive(Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 struct TimeManager {
     tags: Vec<Tag>,
     tag_name: String,
+    tag_name_error: Option<TagNameError>,
+    // Picked from the suggestions, outlined until the new tag field changes
+    highlighted_tag: Option<u32>,
+    is_highlight_scroll_pending: bool,
     minute_rounding_scale: f32,
     minute_rounding_scale_field: String,
     is_rounding_on: bool,
     is_dark_mode: bool,
+    is_reports_open: bool,
+    report_period: ReportPeriod,
+    is_report_rounded: bool,
+    is_report_rolled_up: bool,
+    is_charts_open: bool,
+    chart_week_count: u8,
+    notifications: Notifications,
+    work_schedule: WorkSchedule,
+    break_rules: BreakRules,
+    reminders: ReminderSettings,
+    auto_stop_time_field: String,
+    day_end_reminder_time_field: String,
+    day_end_reminder_sent_on: Option<Date>,
+    is_work_schedule_open: bool,
+    next_invoice_number: u32,
+    is_invoices_open: bool,
+    invoice_client: String,
+    invoice_first_day_field: String,
+    invoice_last_day_field: String,
+    invoice_grouping: InvoiceGrouping,
+    is_archive_open: bool,
+    tag_pending_purge: Option<u32>,
+    tag_sort: TagSort,
+    duration_display: DurationDisplay,
+    clock_format: ClockFormat,
+    idle_minutes: u32,
+    idle_tracker: IdleTracker,
+    idle_interval: Option<(OffsetDateTime, OffsetDateTime)>,
+    idle_reassign_tag: Option<u32>,
+    pomodoro: PomodoroSettings,
+    pomodoro_phase: Option<PomodoroPhase>,
+    // Kept apart from the tags so they don't count as worked time
+    breaks: Vec<TimeSegment>,
+    language: Option<Language>,
+    translator: Rc<Translator>,
+    dragged_tag: Option<usize>,
+    filter_text: String,
+    filter_first_day_field: String,
+    filter_last_day_field: String,
+    is_palette_open: bool,
+    palette_query: String,
+    palette_selection: usize,
+    #[cfg(unix)]
+    control_server: Option<ControlServer>,
+    #[cfg(feature = "http-api")]
+    http_api: HttpApiSettings,
+    #[cfg(feature = "http-api")]
+    http_api_server: Option<HttpApiServer>,
 }
 
 impl TimeManager {
     fn new() -> TimeManager {
         TimeManager {
             tags: Vec::new(),
-            tag_name: "".to_owned(),
+            tag_name: String::new(),
+            tag_name_error: None,
+            highlighted_tag: None,
+            is_highlight_scroll_pending: false,
             minute_rounding_scale: 0.25,
             minute_rounding_scale_field: "0.25".to_owned(),
             is_rounding_on: true,
             is_dark_mode: true,
+            is_reports_open: false,
+            report_period: ReportPeriod::Day,
+            is_report_rounded: false,
+            is_report_rolled_up: true,
+            is_charts_open: false,
+            chart_week_count: 2,
+            notifications: Notifications::new(),
+            work_schedule: WorkSchedule::default(),
+            break_rules: BreakRules::default(),
+            reminders: ReminderSettings::default(),
+            auto_stop_time_field: String::new(),
+            day_end_reminder_time_field: String::new(),
+            day_end_reminder_sent_on: None,
+            is_work_schedule_open: false,
+            next_invoice_number: first_invoice_number(),
+            is_invoices_open: false,
+            invoice_client: String::new(),
+            invoice_first_day_field: String::new(),
+            invoice_last_day_field: String::new(),
+            invoice_grouping: InvoiceGrouping::PerSegment,
+            is_archive_open: false,
+            tag_pending_purge: None,
+            tag_sort: TagSort::Manual,
+            duration_display: DurationDisplay::default(),
+            clock_format: ClockFormat::default(),
+            idle_minutes: 0,
+            idle_tracker: IdleTracker::new(current_time()),
+            idle_interval: None,
+            idle_reassign_tag: None,
+            pomodoro: PomodoroSettings::default(),
+            pomodoro_phase: None,
+            breaks: Vec::new(),
+            language: None,
+            translator: Rc::new(Translator::new(None)),
+            dragged_tag: None,
+            filter_text: String::new(),
+            filter_first_day_field: String::new(),
+            filter_last_day_field: String::new(),
+            is_palette_open: false,
+            palette_query: String::new(),
+            palette_selection: 0,
+            #[cfg(unix)]
+            control_server: None,
+            #[cfg(feature = "http-api")]
+            http_api: HttpApiSettings::default(),
+            #[cfg(feature = "http-api")]
+            http_api_server: None,
         }
     }
     
-    fn new_from_serialized(tag_names: Vec<String>, minute_rounding_scale: f32, is_rounding_on: bool, is_dark_mode: bool) ->TimeManager {
+    fn new_from_serialized(save_data: &SerializedTimeManager) -> TimeManager {
         let mut time_manager = TimeManager {
-            tags: Vec::new(),
-            tag_name: "".to_string(),
-            minute_rounding_scale,
-            minute_rounding_scale_field: minute_rounding_scale.to_string(),
-            is_rounding_on,
-            is_dark_mode,
+            minute_rounding_scale: save_data.minute_rounding_scale,
+            minute_rounding_scale_field: save_data.minute_rounding_scale.to_string(),
+            is_rounding_on: save_data.is_rounding_on,
+            is_dark_mode: save_data.is_dark_mode,
+            work_schedule: save_data.work_schedule,
+            break_rules: save_data.break_rules.clone(),
+            reminders: save_data.reminders,
+            next_invoice_number: save_data.next_invoice_number,
+            tag_sort: save_data.tag_sort,
+            duration_display: save_data.duration_display,
+            clock_format: save_data.clock_format,
+            idle_minutes: save_data.idle_minutes,
+            pomodoro: save_data.pomodoro,
+            breaks: save_data.breaks.iter().map(TimeSegment::from_serialized).collect(),
+            language: save_data.language,
+            translator: Rc::new(Translator::new(save_data.language)),
+            #[cfg(feature = "http-api")]
+            http_api: save_data.http_api.clone(),
+            ..TimeManager::new()
         };
 
-        for name in tag_names.iter() {
+        for serialized_tag in &save_data.tags {
+            time_manager.tags.push(Tag::from_serialized(serialized_tag));
+        }
+
+        for name in &save_data.tag_names {
             time_manager.tags.push(Tag::new(name));
         }
 
+        // Tags saved before they had ids get a fresh one
+        for tag_index in 0..time_manager.tags.len() {
+            let tag_id = time_manager.tags[tag_index].id;
+            if tag_id == 0 || time_manager.tags[..tag_index].iter().any(|tag| tag.id == tag_id) {
+                time_manager.tags[tag_index].id = time_manager.next_tag_id();
+            }
+        }
+
         time_manager
     }
 
+    fn next_tag_id(&self) -> u32 {
+        self.tags.iter().map(|tag| tag.id).max().unwrap_or(0) + 1
+    }
+
+    fn add_tag(&mut self, name: &str) -> Result<(), TagNameError> {
+        let name = validate_tag_name(&self.tags, name, None, None)?;
+        let mut tag = Tag::new(&name);
+        tag.id = self.next_tag_id();
+        self.tags.push(tag);
+
+        Ok(())
+    }
+
+    // Brings an existing tag into view instead of adding another one of the same name, unarchiving it, expanding its
+    // ancestors and clearing a filter that hides it
+    fn reveal_tag(&mut self, tag_index: usize) {
+        if self.tags[tag_index].is_archived {
+            set_archived(&mut self.tags, tag_index, false);
+        }
+        for ancestor in ancestor_indices(&self.tags, tag_index) {
+            self.tags[ancestor].is_collapsed = false;
+        }
+        let filter = TagFilter::new(&self.filter_text, &self.filter_first_day_field, &self.filter_last_day_field, self.clock_format);
+        if !filter.visible_tags(&self.tags)[tag_index] {
+            self.filter_text.clear();
+            self.filter_first_day_field.clear();
+            self.filter_last_day_field.clear();
+        }
+
+        self.highlighted_tag = Some(self.tags[tag_index].id);
+        self.is_highlight_scroll_pending = true;
+    }
+
     fn to_serialized(&self) -> SerializedTimeManager {
-        let mut tag_names: Vec<String> = vec![];
-        for tag in self.tags.iter() {
-            tag_names.push(tag.name.clone());
+        SerializedTimeManager {
+            tag_names: Vec::new(),
+            minute_rounding_scale: self.minute_rounding_scale,
+            is_rounding_on: self.is_rounding_on,
+            is_dark_mode: self.is_dark_mode,
+            next_invoice_number: self.next_invoice_number,
+            work_schedule: self.work_schedule,
+            break_rules: self.break_rules.clone(),
+            reminders: self.reminders,
+            tag_sort: self.tag_sort,
+            duration_display: self.duration_display,
+            clock_format: self.clock_format,
+            idle_minutes: self.idle_minutes,
+            pomodoro: self.pomodoro,
+            language: self.language,
+            #[cfg(feature = "http-api")]
+            http_api: self.http_api.clone(),
+            tags: self.tags.iter().map(Tag::to_serialized).collect(),
+            breaks: self.breaks.iter().map(TimeSegment::to_serialized).collect(),
+        }
+    }
+
+    // True when a warning was sent or reset, which is saved
+    fn check_budgets(&mut self, now: OffsetDateTime) -> bool {
+        let mut is_changed = false;
+
+        for tag in &mut self.tags {
+            let Some(budget) = tag.budget.filter(|_| !tag.is_archived) else {
+                is_changed |= tag.budget_warning_sent_at.take().is_some();
+                continue;
+            };
+
+            let first_day = budget.period.first_day(now.date());
+            let is_exceeded = budget.is_exceeded(tag.hours_since(first_day, now));
+            let is_warning_sent = tag.budget_warning_sent_at.is_some_and(|sent_at| sent_at.date() >= first_day);
+            if is_exceeded && !is_warning_sent && budget.is_notification_on {
+                let (summary_id, kind) = match budget.kind {
+                    BudgetKind::Target => ("budget-target-reached", "target"),
+                    BudgetKind::Cap => ("budget-cap-exceeded", "cap"),
+                };
+                let summary = self.translator.tr_args(summary_id, &[("tag", tag.name.as_str().into())]);
+                let body = self.translator.tr_args("budget-notification-body", &[
+                    ("kind", kind.into()),
+                    ("hours", self.duration_display.format(budget.hours).into()),
+                    ("period", self.translator.tr(budget.period.label()).to_lowercase().into()),
+                    ("tag", tag.name.as_str().into()),
+                ]);
+                self.notifications.send(&summary, &body);
+            }
+            if is_exceeded && !is_warning_sent {
+                tag.budget_warning_sent_at = Some(now);
+                is_changed = true;
+            } else if !is_exceeded && tag.budget_warning_sent_at.is_some() {
+                tag.budget_warning_sent_at = None;
+                is_changed = true;
+            }
         }
 
-        SerializedTimeManager::new(tag_names, self.minute_rounding_scale, self.is_rounding_on, self.is_dark_mode)
+        is_changed
     }
-}
 
-impl App for TimeManager {
-    fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
-        let mut tags_to_be_deleted: Vec<u16> = Vec::new();
-        let mut is_changes_made = false;
+    // The tags in the order they are listed in the central panel, which is also the order of the Alt+1 to Alt+9 shortcuts
+    fn displayed_tags(&self) -> Vec<(usize, usize)> {
+        let filter = TagFilter::new(&self.filter_text, &self.filter_first_day_field, &self.filter_last_day_field, self.clock_format);
+        let is_filtered_tag = filter.visible_tags(&self.tags);
 
-        if self.is_dark_mode {
-            ctx.set_visuals(Visuals::dark());
-        } else {
-            ctx.set_visuals(Visuals::light());
+        tag_order(&self.tags, true, self.tag_sort).into_iter()
+            .filter(|&(tag_index, _)| is_filtered_tag[tag_index])
+            .collect()
+    }
+
+    fn run_command(&mut self, ctx: &Context, command: Command) -> bool {
+        match command {
+            Command::ToggleTag(tag_id) => {
+                let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == tag_id && !tag.is_archived) else {
+                    return false;
+                };
+                tag.toggle_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                true
+            },
+            Command::StopAll => {
+                let mut is_any_stopped = false;
+                for tag in self.tags.iter_mut().filter(|tag| tag.is_active_segment) {
+                    tag.end_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                    tag.calculate_total();
+                    is_any_stopped = true;
+                }
+                is_any_stopped
+            },
+            Command::NewTag => {
+                ctx.memory().request_focus(Id::new(NEW_TAG_FIELD_ID));
+                false
+            },
+            Command::Filter => {
+                ctx.memory().request_focus(Id::new(FILTER_FIELD_ID));
+                false
+            },
+            Command::OpenReports => {
+                self.is_reports_open = true;
+                false
+            },
+            Command::OpenCharts => {
+                self.is_charts_open = true;
+                false
+            },
+            Command::OpenInvoices => {
+                self.is_invoices_open = true;
+                false
+            },
+            Command::OpenArchive => {
+                self.is_archive_open = true;
+                false
+            },
+            Command::OpenWorkHours => {
+                self.is_work_schedule_open = true;
+                false
+            },
+            Command::ToggleDarkMode => {
+                self.is_dark_mode = !self.is_dark_mode;
+                true
+            },
         }
+    }
 
-        TopBottomPanel::top("Panel").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                // Adding new tag
-                let new_tag_response = ui.add(TextEdit::singleline( &mut self.tag_name).hint_text("Enter Tag Name"));
-                if new_tag_response.lost_focus() && ui.input().key_pressed(Key::Enter) {
-                    if !self.tag_name.is_empty() {
-                        self.tags.push(Tag::new(self.tag_name.as_str()));
-                        self.tag_name = "".to_string();
+    fn handle_shortcuts(&mut self, ctx: &Context) -> bool {
+        let mut is_changed = false;
 
-                        is_changes_made = true;
-                    }
+        if ctx.input_mut().consume_key(Modifiers::COMMAND, Key::K) {
+            self.is_palette_open = !self.is_palette_open;
+            self.palette_query.clear();
+            self.palette_selection = 0;
+        }
+        if ctx.input_mut().consume_key(Modifiers::COMMAND, Key::F) {
+            is_changed |= self.run_command(ctx, Command::Filter);
+        }
+        if ctx.input_mut().consume_key(Modifiers::COMMAND, Key::N) {
+            is_changed |= self.run_command(ctx, Command::NewTag);
+        }
+        if ctx.input_mut().consume_key(Modifiers::ALT, Key::Num0) {
+            is_changed |= self.run_command(ctx, Command::StopAll);
+        }
+
+        let displayed_tags = self.displayed_tags();
+        for (key, (tag_index, _)) in TAG_SHORTCUT_KEYS.into_iter().zip(displayed_tags) {
+            if ctx.input_mut().consume_key(Modifiers::ALT, key) {
+                is_changed |= self.run_command(ctx, Command::ToggleTag(self.tags[tag_index].id));
+            }
+        }
+
+        is_changed
+    }
+
+    fn show_command_palette(&mut self, ctx: &Context) -> bool {
+        if !self.is_palette_open {
+            return false;
+        }
+
+        let commands = matching_commands(&self.tags, &self.translator, &self.palette_query);
+        self.palette_selection = self.palette_selection.min(commands.len().saturating_sub(1));
+        let mut selected_command: Option<Command> = None;
+
+        {
+            let mut input = ctx.input_mut();
+            if input.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                self.palette_selection = (self.palette_selection + 1).min(commands.len().saturating_sub(1));
+            }
+            if input.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                self.palette_selection = self.palette_selection.saturating_sub(1);
+            }
+            if input.consume_key(Modifiers::NONE, Key::Enter) {
+                selected_command = commands.get(self.palette_selection).map(|(command, _)| *command);
+            }
+            if input.consume_key(Modifiers::NONE, Key::Escape) {
+                self.is_palette_open = false;
+            }
+        }
+        if !self.is_palette_open {
+            return false;
+        }
+
+        egui::Window::new(self.translator.tr("command-palette"))
+            .id(Id::new("command_palette_window"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0., 60.])
+            .show(ctx, |ui| {
+                let query_response = ui.add(TextEdit::singleline(&mut self.palette_query)
+                    .hint_text(self.translator.tr("command-palette-hint"))
+                    .desired_width(320.));
+                query_response.request_focus();
+                if query_response.changed() {
+                    self.palette_selection = 0;
                 }
-                if ui.add(Button::new("Add New Tag")).clicked() {
-                    if !self.tag_name.is_empty() {
-                        self.tags.push(Tag::new(self.tag_name.as_str()));
-                        self.tag_name = "".to_string();
 
-                        is_changes_made = true;
+                for (command_index, (command, label)) in commands.iter().enumerate() {
+                    if ui.selectable_label(command_index == self.palette_selection, label).clicked() {
+                        selected_command = Some(*command);
                     }
                 }
+                if commands.is_empty() {
+                    ui.weak(self.translator.tr("no-matches"));
+                }
             });
 
-            ui.horizontal(|ui| {
-                // Rounding feature
-                ui.label("Minute Rounding Scale: ");
-                let minute_rounding_scale_response = ui.text_edit_singleline(&mut self.minute_rounding_scale_field);
+        match selected_command {
+            Some(command) => {
+                self.is_palette_open = false;
+                self.run_command(ctx, command)
+            },
+            None => false,
+        }
+    }
 
-                if minute_rounding_scale_response.lost_focus() {
-                    match self.minute_rounding_scale_field.parse::<f32>() {
-                        Ok(user_rounding_scale) => {
-                            if self.minute_rounding_scale != user_rounding_scale {
-                                self.minute_rounding_scale = user_rounding_scale;
+    fn check_idle(&mut self, ctx: &Context) {
+        let now = current_time();
+        {
+            let input = ctx.input();
+            if !input.events.is_empty() || input.pointer.is_moving() {
+                self.idle_tracker.record_input(now);
+            }
+        }
 
-                                is_changes_made = true;
-                            }
-                        },
-                        Err(_) => {
-                            self.minute_rounding_scale_field = self.minute_rounding_scale.to_string();
-                        },
+        if self.idle_minutes == 0 || !self.tags.iter().any(|tag| tag.is_active_segment) {
+            self.idle_tracker.reset();
+            return;
+        }
+
+        self.idle_tracker.poll_system_idle(now);
+        let idle_interval = self.idle_tracker.check(now, Duration::minutes(i64::from(self.idle_minutes)));
+        // Only the first idle interval is asked about until it's answered
+        if self.idle_interval.is_none() {
+            self.idle_interval = idle_interval;
+        }
+    }
+
+    fn end_break(&mut self, now: OffsetDateTime) -> bool {
+        let Some(segment) = self.breaks.last_mut().filter(|segment| segment.end_time.is_none()) else {
+            return false;
+        };
+        segment.end_time = Some(now.max(segment.start_time.unwrap()));
+        segment.calculate_total_hours();
+
+        true
+    }
+
+    // Starting a tag begins a work interval. When it runs out the tag is stopped at its end and a break is logged,
+    // which a tag started during the break cuts short. Other tags running alongside are left alone
+    fn check_pomodoro(&mut self, now: OffsetDateTime) -> bool {
+        if !self.pomodoro.is_enabled {
+            let is_break_ended = match self.pomodoro_phase.take() {
+                Some(PomodoroPhase::Break { ends_at }) => self.end_break(now.min(ends_at)),
+                _ => false,
+            };
+            return is_break_ended;
+        }
+
+        let mut is_changed = false;
+        match self.pomodoro_phase {
+            Some(PomodoroPhase::Work { tag_id, ends_at }) => {
+                if !self.tags.iter().any(|tag| tag.id == tag_id && tag.is_active_segment) {
+                    // Stopped early, which doesn't count as a pomodoro
+                    self.pomodoro_phase = None;
+                } else if now >= ends_at {
+                    let tag = self.tags.iter_mut().find(|tag| tag.id == tag_id).unwrap();
+                    let segment = tag.time_segments.last_mut().unwrap();
+                    segment.end_time = Some(ends_at.max(segment.start_time.unwrap()));
+                    segment.calculate_total_hours();
+                    segment.is_pomodoro = true;
+                    tag.is_active_segment = false;
+                    tag.calculate_total();
+                    self.breaks.push(TimeSegment::from_times(ends_at, None));
+                    self.pomodoro_phase = Some(PomodoroPhase::Break { ends_at: ends_at + self.pomodoro.break_interval() });
+
+                    let tag_name = self.tags.iter().find(|tag| tag.id == tag_id).unwrap().name.clone();
+                    self.notifications.send(&self.translator.tr_args("pomodoro-completed", &[("tag", tag_name.into())]),
+                                            &self.translator.tr_args("break-started", &[("minutes", self.pomodoro.break_minutes.into())]));
+                    is_changed = true;
+                }
+            },
+            Some(PomodoroPhase::Break { ends_at }) => {
+                let break_start = self.breaks.last().and_then(|segment| segment.start_time).unwrap_or(now);
+                let is_tag_started = self.tags.iter()
+                    .any(|tag| tag.is_active_segment && tag.time_segments.last().unwrap().start_time.unwrap() >= break_start);
+                if now >= ends_at || is_tag_started {
+                    self.end_break(now.min(ends_at));
+                    self.pomodoro_phase = None;
+                    if !is_tag_started {
+                        self.notifications.send(&self.translator.tr("break-over"), &self.translator.tr("break-over-body"));
                     }
+                    is_changed = true;
                 }
+            },
+            None => (),
+        }
 
-                let rounding_enabled_response = ui.checkbox(&mut self.is_rounding_on, "Minute Rounding");
-                if rounding_enabled_response.changed() {
-                    is_changes_made = true;
-                }
+        // The interval runs from the segment's start, which may be before the app noticed it. Segments that were
+        // already running through the last break don't begin one
+        if self.pomodoro_phase.is_none() {
+            let last_break_end = self.breaks.last().and_then(|segment| segment.end_time);
+            let started_segment = self.tags.iter()
+                .filter(|tag| tag.is_active_segment)
+                .map(|tag| (tag.id, tag.time_segments.last().unwrap().start_time.unwrap()))
+                .filter(|(_, start_time)| last_break_end.is_none_or(|last_break_end| *start_time >= last_break_end))
+                .min_by_key(|(_, start_time)| *start_time);
+            if let Some((tag_id, start_time)) = started_segment {
+                self.pomodoro_phase = Some(PomodoroPhase::Work { tag_id, ends_at: start_time + self.pomodoro.work_interval() });
+            }
+        }
 
-                ui.separator();
-                let dark_mode_enabled_response = ui.checkbox(&mut self.is_dark_mode, "Dark Mode");
-                if dark_mode_enabled_response.changed() {
-                    is_changes_made = true;
+        is_changed
+    }
+
+    fn check_reminders(&mut self, now: OffsetDateTime) -> bool {
+        let mut is_changed = false;
+
+        // Also catches segments left running while the program was closed
+        if let Some(auto_stop_time) = self.reminders.auto_stop_time {
+            let mut stopped_tags: Vec<String> = Vec::new();
+            for tag in self.tags.iter_mut().filter(|tag| tag.is_active_segment) {
+                let segment = tag.time_segments.last_mut().unwrap();
+                let stop_time = next_time_of_day(segment.start_time.unwrap(), auto_stop_time);
+                if now >= stop_time {
+                    segment.end_time = Some(stop_time);
+                    segment.calculate_total_hours();
+                    tag.is_active_segment = false;
+                    tag.calculate_total();
+                    stopped_tags.push(tag.name.clone());
                 }
-            });
-        });
+            }
+            if !stopped_tags.is_empty() {
+                self.notifications.send(&self.translator.tr("auto-stopped"), &stopped_tags.join(", "));
+                is_changed = true;
+            }
+        }
 
-        CentralPanel::default().show(ctx, |ui| {
-            ScrollArea::vertical().show(ui, |ui| {
+        let long_run_hours = self.reminders.long_run_hours.unwrap_or(f64::INFINITY);
+        for tag in &mut self.tags {
+            let is_long_run = tag.is_active_segment && tag.time_segments.last().unwrap().elapsed_hours(now) > long_run_hours;
+            if is_long_run && !tag.is_long_run_warning_sent {
+                self.notifications.send(&self.translator.tr_args("long-run", &[("tag", tag.name.as_str().into())]),
+                                        &self.translator.tr_args("long-run-body", &[("hours", long_run_hours.into())]));
+            }
+            tag.is_long_run_warning_sent = is_long_run;
+        }
 
-                let is_rounding_on = self.is_rounding_on;
-                let minute_rounding_scale = self.minute_rounding_scale;
+        // Only when there is a session left to review
+        if let Some(reminder_time) = self.reminders.day_end_reminder_time {
+            let today = now.date();
+            let is_session_open = self.tags.iter().any(|tag| !tag.time_segments.is_empty());
+            if now.time() >= reminder_time && is_session_open && self.day_end_reminder_sent_on != Some(today) {
+                self.day_end_reminder_sent_on = Some(today);
+                self.notifications.send(&self.translator.tr("day-end-reminder"), &self.translator.tr("day-end-reminder-body"));
+            }
+        }
 
-                for (tag_index, tag) in self.tags.iter_mut().enumerate() {
-                    ui.horizontal(|ui| {
-                        let button_text;
-                        if tag.is_active_segment {
-                            button_text = "Stop";
-                        } else {
-                            button_text = "Start";
-                        }
+        is_changed
+    }
 
-                        if ui.add(Button::new(button_text)).clicked() {
-                            if tag.is_active_segment {
-                                tag.end_time_segment(is_rounding_on, minute_rounding_scale);
-                                tag.calculate_total();
-                            } else {
-                                tag.start_time_segment(is_rounding_on, minute_rounding_scale);
-                            }
-                        }
+    #[cfg(unix)]
+    fn start_control_server(&mut self, ctx: &Context) {
+        let ctx = ctx.clone();
+        match ControlServer::start(&control_socket_path(), move || ctx.request_repaint()) {
+            Ok(control_server) => self.control_server = Some(control_server),
+            Err(error_message) => println!("Unable to open the control socket: {error_message}"),
+        }
+    }
 
-                        ui.separator();
+    #[cfg(not(unix))]
+    #[allow(clippy::unused_self)]
+    fn start_control_server(&mut self, _ctx: &Context) {}
+
+    // Answers the requests that came in over the control socket since the last frame
+    #[cfg(unix)]
+    fn service_control_socket(&mut self) -> bool {
+        let mut is_changed = false;
+
+        while let Some(call) = self.control_server.as_ref().and_then(ControlServer::next_call) {
+            let reply = match &call.request {
+                Ok(request) => {
+                    let reply = self.handle_control_request(request, current_time());
+                    is_changed |= reply.is_ok() && request.is_modifying();
+                    reply
+                },
+                Err(message) => Err(message.clone()),
+            };
+            call.reply(reply);
+        }
 
-                        ui.label(&tag.name);
-                        ui.separator();
-                        ui.label(format!("Total Hours: {}", &tag.total_time.to_string()));
-                        // ui.separator();
+        is_changed
+    }
 
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
-                            if ui.add(Button::new("Remove Tag")).clicked() {
-                                tags_to_be_deleted.push(tag_index as u16);
+    #[cfg(unix)]
+    fn handle_control_request(&mut self, request: &ControlRequest, now: OffsetDateTime) -> Result<String, String> {
+        let find_control_tag = |tags: &[Tag], text: &str| find_tag(tags, text).ok_or_else(|| format!("there is no tag \"{text}\""));
+
+        match request {
+            ControlRequest::List => {
+                let mut reply = String::new();
+                for (tag_index, _) in tag_order(&self.tags, false, TagSort::Manual) {
+                    let tag = &self.tags[tag_index];
+                    if tag.is_archived {
+                        continue;
+                    }
+                    let state = if tag.is_active_segment { "running" } else { "stopped" };
+                    writeln!(reply, "{}\t{}\t{state}", tag.id, tag_path(&self.tags, tag_index)).unwrap();
+                }
+                Ok(reply)
+            },
+            ControlRequest::Status => {
+                let mut reply = String::new();
+                for (tag_index, tag) in self.tags.iter().enumerate().filter(|(_, tag)| tag.is_active_segment) {
+                    let segment = tag.time_segments.last().unwrap();
+                    writeln!(reply, "{}\t{}\t{}", tag_path(&self.tags, tag_index),
+                             self.clock_format.format_time(segment.start_time.unwrap()), self.duration_display.format(segment.elapsed_hours(now))).unwrap();
+                }
+                Ok(reply)
+            },
+            ControlRequest::Start(text) => {
+                let tag_index = find_control_tag(&self.tags, text)?;
+                let path = tag_path(&self.tags, tag_index);
+                if self.tags[tag_index].is_active_segment {
+                    return Err(format!("{path} is already running"));
+                }
 
-                                is_changes_made = true;
-                            }
-                        });
+                self.tags[tag_index].start_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                Ok(format!("started {path}\n"))
+            },
+            ControlRequest::Stop(text) => {
+                let tag_indices: Vec<usize> = match text {
+                    Some(text) => {
+                        let tag_index = find_control_tag(&self.tags, text)?;
+                        if !self.tags[tag_index].is_active_segment {
+                            return Err(format!("{} isn't running", tag_path(&self.tags, tag_index)));
+                        }
+                        vec![tag_index]
+                    },
+                    None => (0..self.tags.len()).filter(|&tag_index| self.tags[tag_index].is_active_segment).collect(),
+                };
+
+                let mut reply = String::new();
+                for tag_index in tag_indices {
+                    self.tags[tag_index].end_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                    self.tags[tag_index].calculate_total();
+                    writeln!(reply, "stopped {}", tag_path(&self.tags, tag_index)).unwrap();
+                }
+                Ok(reply)
+            },
+            ControlRequest::AddSegment { date, start_time, end_time, tag } => {
+                let tag_index = find_control_tag(&self.tags, tag)?;
+                let date = self.clock_format.parse_date(date).ok_or_else(|| format!("\"{date}\" isn't a date"))?;
+                let [start_time, end_time] = [start_time, end_time].map(|time_text| {
+                    let time_of_day = self.clock_format.parse_time(time_text).ok_or_else(|| format!("\"{time_text}\" isn't a time"))?;
+                    Ok::<OffsetDateTime, String>(date.with_time(time_of_day).assume_offset(now.offset()))
+                });
+                let (start_time, end_time) = (start_time?, end_time?);
+                if end_time <= start_time {
+                    return Err("the end has to be after the start".to_owned());
+                }
 
-                    });
+                let segment = TimeSegment::from_times(start_time, Some(end_time));
+                let hours = segment.hours_total;
+                self.tags[tag_index].insert_segment(segment);
+                Ok(format!("added {} to {}\n", self.duration_display.format(hours), tag_path(&self.tags, tag_index)))
+            },
+        }
+    }
 
-                    let mut segments_to_be_deleted: Vec<u16> = Vec::new();
-                    ui.vertical(|ui| {
-                        for (segment_index, segment) in tag.time_segments.iter_mut().enumerate() {
-                            ui.horizontal(|ui| {
-                                ui.add_space(40f32);
-                                let start_hour_text = TextEdit::singleline(&mut segment.start_time_hour_field)
-                                    .desired_width(25.);
-                                let start_time_hour_field_response = ui.add(start_hour_text);
+    // Stops the server and starts it again with the current settings when it's enabled
+    #[cfg(feature = "http-api")]
+    fn restart_http_api(&mut self, ctx: &Context) {
+        self.http_api_server = None;
+        if !self.http_api.is_enabled {
+            return;
+        }
+        if self.http_api.token.is_empty() {
+            self.http_api.token = generate_token();
+        }
 
-                                ui.label(":");
+        let ctx = ctx.clone();
+        match HttpApiServer::start(self.http_api.port, &self.http_api.token, move || ctx.request_repaint()) {
+            Ok(http_api_server) => self.http_api_server = Some(http_api_server),
+            Err(error_message) => self.notifications.send(&self.translator.tr("http-api-unavailable"), &error_message.to_string()),
+        }
+    }
 
-                                let start_minute_text = TextEdit::singleline(&mut segment.start_time_minute_field)
-                                    .desired_width(25.);
-                                let start_time_minute_field_response = ui.add(start_minute_text);
+    #[cfg(feature = "http-api")]
+    fn service_http_api(&mut self) -> bool {
+        let mut is_changed = false;
 
-                                ui.add_space(20.);
-                                ui.label("-");
-                                ui.add_space(20.);
+        while let Some(call) = self.http_api_server.as_ref().and_then(HttpApiServer::next_call) {
+            let reply = self.handle_api_request(call.request, current_time());
+            is_changed |= reply.is_ok() && matches!(call.request, ApiRequest::Start(_) | ApiRequest::Stop(_) | ApiRequest::StopAll);
+            call.reply(reply);
+        }
 
-                                if segment.end_time.is_some() {
-                                    let end_hour_text = TextEdit::singleline(&mut segment.end_time_hour_field)
-                                        .desired_width(25.);
-                                    let end_time_hour_response = ui.add(end_hour_text);
-
-                                    ui.label(":");
-
-                                    let end_minute_text = TextEdit::singleline(&mut segment.end_time_minute_field)
-                                        .desired_width(25.);
-                                    let end_time_minute_response = ui.add(end_minute_text);
-
-                                    if end_time_hour_response.lost_focus() {
-                                        match segment.end_time_hour_field.parse::<u8>() {
-                                            Ok(user_hour) => {
-                                                if user_hour < 24 {
-                                                    let old_time_hms = segment.end_time.unwrap().to_hms();
-                                                    let updated_time = segment.end_time.unwrap().replace_time(Time::from_hms(user_hour, old_time_hms.1, old_time_hms.2).unwrap());
-                                                    segment.end_time = Some(updated_time);
-                                                } else {
-                                                    segment.end_time_hour_field = segment.end_time.unwrap().to_hms().0.to_string();
-                                                }
-                                            },
-                                            Err(_) => {
-                                                segment.end_time_hour_field = segment.end_time.unwrap().to_hms().0.to_string();
-                                            },
-                                        }
+        is_changed
+    }
 
-                                        segment.calculate_total_hours();
-                                    }
+    #[cfg(feature = "http-api")]
+    fn handle_api_request(&mut self, request: ApiRequest, now: OffsetDateTime) -> Result<String, (u16, String)> {
+        let find_api_tag = |tags: &[Tag], tag_id: u32| tags.iter()
+            .position(|tag| tag.id == tag_id && !tag.is_archived)
+            .ok_or_else(|| (404, format!("there is no tag with the id {tag_id}")));
+
+        let body = match request {
+            ApiRequest::Tags => {
+                let tags: Vec<TagView> = tag_order(&self.tags, false, TagSort::Manual).into_iter()
+                    .map(|(tag_index, _)| TagView::new(&self.tags, tag_index, now))
+                    .collect();
+                serde_json::to_string(&tags)
+            },
+            ApiRequest::Segments { tag_id, first_day, last_day } => {
+                serde_json::to_string(&segment_views(&self.tags, tag_id, first_day, last_day, now))
+            },
+            ApiRequest::Report(period) => {
+                let billing_rounding_scale = if self.is_rounding_on { Some(self.minute_rounding_scale) } else { None };
+                let report = Report::new(&self.tags, ReportOptions { period, rounding_scale: None, billing_rounding_scale, is_rolled_up: false });
+                serde_json::to_string(&ReportView::new(report))
+            },
+            ApiRequest::Start(tag_id) => {
+                let tag_index = find_api_tag(&self.tags, tag_id)?;
+                if self.tags[tag_index].is_active_segment {
+                    return Err((409, format!("{} is already running", tag_path(&self.tags, tag_index))));
+                }
 
-                                    if end_time_minute_response.lost_focus() {
-                                        match segment.end_time_minute_field.parse::<u8>() {
-                                            Ok(user_minute) => {
-                                                if user_minute < 60 {
-                                                    let old_time_hms = segment.end_time.unwrap().to_hms();
-                                                    let updated_time = segment.end_time.unwrap().replace_time(Time::from_hms(old_time_hms.0, user_minute, old_time_hms.2).unwrap());
-                                                    segment.end_time = Some(updated_time);
-                                                } else {
-                                                    segment.end_time_minute_field = segment.end_time.unwrap().to_hms().1.to_string();
-                                                }
-                                            },
-                                            Err(_) => {
-                                                segment.end_time_minute_field = segment.end_time.unwrap().to_hms().1.to_string();
-                                            },
-                                        }
+                self.tags[tag_index].start_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                serde_json::to_string(&TagView::new(&self.tags, tag_index, now))
+            },
+            ApiRequest::Stop(tag_id) => {
+                let tag_index = find_api_tag(&self.tags, tag_id)?;
+                if !self.tags[tag_index].is_active_segment {
+                    return Err((409, format!("{} isn't running", tag_path(&self.tags, tag_index))));
+                }
 
-                                        segment.calculate_total_hours();
-                                    }
-                                }
-                                ui.separator();
-                                ui.label(format!("Hours: {:.2}", segment.hours_total));
+                self.tags[tag_index].end_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                self.tags[tag_index].calculate_total();
+                serde_json::to_string(&TagView::new(&self.tags, tag_index, now))
+            },
+            ApiRequest::StopAll => {
+                let mut stopped_tags = Vec::new();
+                for tag_index in 0..self.tags.len() {
+                    if self.tags[tag_index].is_active_segment {
+                        self.tags[tag_index].end_time_segment(self.is_rounding_on, self.minute_rounding_scale);
+                        self.tags[tag_index].calculate_total();
+                        stopped_tags.push(TagView::new(&self.tags, tag_index, now));
+                    }
+                }
+                serde_json::to_string(&stopped_tags)
+            },
+        };
 
-                                ui.add_space(20.);
-                                // ui.separator();
+        Ok(body.unwrap())
+    }
 
-                                ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
-                                    if ui.add(Button::new("Remove Time Segment")).clicked() {
-                                        segments_to_be_deleted.push(segment_index as u16);
-                                    }
-                                });
+    fn show_idle_window(&mut self, ctx: &Context) -> bool {
+        let Some((idle_start, idle_end)) = self.idle_interval else {
+            return false;
+        };
+        let translator = Rc::clone(&self.translator);
+        let mut is_answered = false;
+        let mut is_discarded = false;
+        let mut reassigned_tag: Option<u32> = None;
+
+        egui::Window::new(translator.tr("idle-time"))
+            .id(Id::new("idle_window"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0., 0.])
+            .show(ctx, |ui| {
+                ui.label(translator.tr_args("idle-interval", &[
+                    ("start", self.clock_format.format_time(idle_start).into()),
+                    ("end", self.clock_format.format_time(idle_end).into()),
+                    ("hours", self.duration_display.format((idle_end - idle_start).as_seconds_f64() / 3600f64).into()),
+                ]));
+
+                ui.horizontal(|ui| {
+                    if ui.add(Button::new(translator.tr("keep-idle-time"))).clicked() {
+                        is_answered = true;
+                    }
+                    if ui.add(Button::new(translator.tr("discard-idle-time"))).clicked() {
+                        is_answered = true;
+                        is_discarded = true;
+                    }
 
-                                if start_time_hour_field_response.lost_focus() {
-                                    match segment.start_time_hour_field.parse::<u8>() {
-                                        Ok(user_hour) => {
-                                            if user_hour < 24 {
-                                                let old_time_hms = segment.start_time.unwrap().to_hms();
-                                                let updated_time = segment.start_time.unwrap().replace_time(Time::from_hms(user_hour, old_time_hms.1, old_time_hms.2).unwrap());
-                                                segment.start_time = Some(updated_time);
-                                            } else {
-                                                segment.start_time_hour_field = segment.start_time.unwrap().to_hms().0.to_string();
-                                            }
-                                        },
-                                        Err(_) => {
-                                            segment.start_time_hour_field = segment.start_time.unwrap().to_hms().0.to_string();
-                                        },
-                                    }
+                    ui.separator();
+                    let tag_options: Vec<(u32, String)> = tag_order(&self.tags, false, TagSort::Manual).into_iter()
+                        .filter(|&(tag_index, _)| !self.tags[tag_index].is_archived)
+                        .map(|(tag_index, _)| (self.tags[tag_index].id, tag_path(&self.tags, tag_index)))
+                        .collect();
+                    let selected_path = tag_options.iter()
+                        .find(|(tag_id, _)| Some(*tag_id) == self.idle_reassign_tag)
+                        .map_or(String::new(), |(_, path)| path.clone());
+                    ComboBox::from_id_source("idle_reassign_tag")
+                        .selected_text(selected_path)
+                        .show_ui(ui, |ui| {
+                            for (tag_id, path) in &tag_options {
+                                ui.selectable_value(&mut self.idle_reassign_tag, Some(*tag_id), path);
+                            }
+                        });
+                    if ui.add_enabled(self.idle_reassign_tag.is_some(), Button::new(translator.tr("reassign-idle-time"))).clicked() {
+                        is_answered = true;
+                        reassigned_tag = self.idle_reassign_tag;
+                    }
+                });
+            });
 
-                                    if segment.end_time.is_some() {
-                                        segment.calculate_total_hours();
-                                    }
-                                }
+        if !is_answered {
+            return false;
+        }
+        self.idle_interval = None;
+        if !is_discarded && reassigned_tag.is_none() {
+            return false;
+        }
 
-                                if start_time_minute_field_response.lost_focus() {
-                                    match segment.start_time_minute_field.parse::<u8>() {
-                                        Ok(user_minute) => {
-                                            if user_minute < 60 {
-                                                let old_time_hms = segment.start_time.unwrap().to_hms();
-                                                let updated_time = segment.start_time.unwrap().replace_time(Time::from_hms(old_time_hms.0, user_minute, old_time_hms.2).unwrap());
-                                                segment.start_time = Some(updated_time);
-                                            } else {
-                                                segment.start_time_minute_field = segment.start_time.unwrap().to_hms().1.to_string();
-                                            }
-                                        },
-                                        Err(_) => {
-                                            segment.start_time_minute_field = segment.start_time.unwrap().to_hms().1.to_string();
-                                        },
-                                    }
+        // The running segments are split around the idle interval, which then either goes away or moves to one tag
+        let removed_hours: f64 = self.tags.iter_mut().map(|tag| tag.cut_interval(idle_start, idle_end)).sum();
+        if removed_hours > 0f64 {
+            if let Some(tag) = reassigned_tag.and_then(|tag_id| self.tags.iter_mut().find(|tag| tag.id == tag_id)) {
+                tag.insert_segment(TimeSegment::from_times(idle_start, Some(idle_end)));
+            }
+        }
 
-                                    if segment.end_time.is_some() {
-                                        segment.calculate_total_hours();
-                                    }
-                                }
+        true
+    }
 
-                            });
-                        }
+    fn show_work_schedule_window(&mut self, ctx: &Context) -> bool {
+        let mut is_changed = false;
+        let translator = &self.translator;
 
-                        for segment_index in segments_to_be_deleted.drain(..) {
-                            if tag.time_segments.len() - 1 == segment_index as usize {
-                                tag.is_active_segment = false;
-                            }
+        egui::Window::new(translator.tr("work-hours")).id(Id::new("work_hours_window")).open(&mut self.is_work_schedule_open).show(ctx, |ui| {
+            Grid::new("work_schedule_grid").show(ui, |ui| {
+                for (weekday_name, expected_hours) in WEEKDAY_NAMES.iter().zip(self.work_schedule.expected_hours.iter_mut()) {
+                    ui.label(translator.tr(weekday_name));
+                    is_changed |= ui.add(DragValue::new(expected_hours).speed(0.25).clamp_range(0.0..=24.0)).changed();
+                    ui.end_row();
+                }
+            });
 
-                            tag.time_segments.remove(segment_index as usize);
+            ui.separator();
+            let break_rules = &mut self.break_rules;
+            is_changed |= ui.checkbox(&mut break_rules.is_enabled, translator.tr("break-rules")).changed();
+            ui.add_enabled_ui(break_rules.is_enabled, |ui| {
+                let mut rule_to_be_removed: Option<usize> = None;
+                Grid::new("break_rules_grid").show(ui, |ui| {
+                    for (rule_index, rule) in break_rules.rules.iter_mut().enumerate() {
+                        ui.label(translator.tr("break-rule-after"));
+                        is_changed |= ui.add(DragValue::new(&mut rule.worked_hours).speed(0.25).clamp_range(0.0..=24.0)
+                            .suffix(translator.tr("hours-suffix"))).changed();
+                        ui.label(translator.tr("break-rule-at-least"));
+                        is_changed |= ui.add(DragValue::new(&mut rule.break_minutes).clamp_range(0..=240)
+                            .suffix(translator.tr("minutes-suffix"))).changed();
+                        if ui.small_button("✖").clicked() {
+                            rule_to_be_removed = Some(rule_index);
                         }
+                        ui.end_row();
+                    }
+                });
+                if let Some(rule_index) = rule_to_be_removed {
+                    break_rules.rules.remove(rule_index);
+                    is_changed = true;
+                }
+                if ui.add(Button::new(translator.tr("add-break-rule"))).clicked() {
+                    break_rules.rules.push(BreakRule { worked_hours: 6.0, break_minutes: 30 });
+                    is_changed = true;
+                }
 
-                        tag.calculate_total();
+                ui.horizontal(|ui| {
+                    ui.label(translator.tr("minimum-break"));
+                    is_changed |= ui.add(DragValue::new(&mut break_rules.minimum_break_minutes).clamp_range(1..=60)
+                        .suffix(translator.tr("minutes-suffix"))).changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label(translator.tr("max-hours-without-break"));
+                    is_changed |= ui.add(DragValue::new(&mut break_rules.max_hours_without_break).speed(0.25).clamp_range(0.0..=24.0)
+                        .suffix(translator.tr("hours-suffix"))).changed();
+                });
+            });
 
-                        ui.separator();
-                    });
+            ui.separator();
+            ui.strong(translator.tr("reminders"));
+            let reminders = &mut self.reminders;
+            Grid::new("reminders_grid").show(ui, |ui| {
+                let mut is_auto_stop_on = reminders.auto_stop_time.is_some();
+                if ui.checkbox(&mut is_auto_stop_on, translator.tr("auto-stop-at")).changed() {
+                    reminders.auto_stop_time = is_auto_stop_on.then(|| Time::from_hms(20, 0, 0).unwrap());
+                    is_changed = true;
+                }
+                if let Some(auto_stop_time) = reminders.auto_stop_time.as_mut() {
+                    is_changed |= time_of_day_field(ui, Id::new("auto_stop_time"), &mut self.auto_stop_time_field, auto_stop_time, self.clock_format);
                 }
+                ui.end_row();
 
-                ui.add_space(20.);
+                let mut is_long_run_on = reminders.long_run_hours.is_some();
+                if ui.checkbox(&mut is_long_run_on, translator.tr("long-run-after")).changed() {
+                    reminders.long_run_hours = is_long_run_on.then_some(4.0);
+                    is_changed = true;
+                }
+                if let Some(long_run_hours) = reminders.long_run_hours.as_mut() {
+                    is_changed |= ui.add(DragValue::new(long_run_hours).speed(0.25).clamp_range(0.25..=24.0)
+                        .suffix(translator.tr("hours-suffix"))).changed();
+                }
+                ui.end_row();
 
-                for tag_index in tags_to_be_deleted.drain(..) {
-                    self.tags.remove(tag_index as usize);
+                let mut is_day_end_reminder_on = reminders.day_end_reminder_time.is_some();
+                if ui.checkbox(&mut is_day_end_reminder_on, translator.tr("day-end-reminder-at")).changed() {
+                    reminders.day_end_reminder_time = is_day_end_reminder_on.then(|| Time::from_hms(17, 30, 0).unwrap());
+                    is_changed = true;
+                }
+                if let Some(day_end_reminder_time) = reminders.day_end_reminder_time.as_mut() {
+                    is_changed |= time_of_day_field(ui, Id::new("day_end_reminder_time"), &mut self.day_end_reminder_time_field,
+                                                    day_end_reminder_time, self.clock_format);
                 }
+                ui.end_row();
             });
         });
 
-        TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
+        is_changed
+    }
+
+    fn show_reports_window(&mut self, ctx: &Context) {
+        let rounding_scale = if self.is_report_rounded { Some(self.minute_rounding_scale) } else { None };
+        let billing_rounding_scale = if self.is_rounding_on { Some(self.minute_rounding_scale) } else { None };
+        let report = Report::new(&self.tags, ReportOptions {
+            period: self.report_period,
+            rounding_scale,
+            billing_rounding_scale,
+            is_rolled_up: self.is_report_rolled_up,
+        });
+        let notifications = &mut self.notifications;
+        let duration_display = self.duration_display;
+        let clock_format = self.clock_format;
+        let translator = &self.translator;
+
+        egui::Window::new(translator.tr("reports")).id(Id::new("reports_window")).open(&mut self.is_reports_open).show(ctx, |ui| {
             ui.horizontal(|ui| {
-                if ui.add(Button::new("Clear Session")).clicked() {
-                    for tag in self.tags.iter_mut() {
-                        tag.clear_session();
-                    }
+                ui.radio_value(&mut self.report_period, ReportPeriod::Day, translator.tr("period-daily"));
+                ui.radio_value(&mut self.report_period, ReportPeriod::Week, translator.tr("period-weekly"));
+                ui.radio_value(&mut self.report_period, ReportPeriod::Month, translator.tr("period-monthly"));
+                ui.separator();
+                ui.checkbox(&mut self.is_report_rounded, translator.tr("rounded-hours"));
+                ui.checkbox(&mut self.is_report_rolled_up, translator.tr("include-subtags"));
+                ui.separator();
+                if ui.add(Button::new(translator.tr("export-csv"))).clicked() {
+                    save_to_file(Path::new(REPORT_EXPORT_PATH), report_to_csv(&report, duration_display).as_bytes());
+                    notifications.send(&translator.tr("report-exported"), &translator.tr_args("saved-to", &[("path", REPORT_EXPORT_PATH.into())]));
                 }
-                // ui.separator();
-                // ui.label("End session & save");
             });
-        });
 
-        // Save state to file
-        if is_changes_made {
-            let serialized_time_manager = self.to_serialized();
-            let time_manager_as_toml = toml::to_string(&serialized_time_manager).unwrap();
-            save_to_file(time_manager_as_toml);
-        }
-    }
-}
+            ui.separator();
 
-fn save_to_file(save_data: String) {
-    let path = Path::new("daily-time-keeper_save_data.toml");
-    let display = path.display();
+            ScrollArea::both().show(ui, |ui| {
+                Grid::new("report_grid").striped(true).show(ui, |ui| {
+                    ui.strong(translator.tr("period"));
+                    for tag_name in &report.tag_names {
+                        ui.strong(tag_name);
+                    }
+                    ui.strong(translator.tr("total"));
+                    ui.end_row();
 
-    let mut file = File::create(&path).expect(&*format!("Unable to create {}", display));
+                    for row in &report.rows {
+                        if self.report_period == ReportPeriod::Day {
+                            ui.label(clock_format.format_date(row.period_start));
+                        } else {
+                            ui.label(&row.label);
+                        }
+                        for hours in &row.tag_hours {
+                            ui.label(duration_display.format(*hours));
+                        }
+                        ui.label(duration_display.format(row.total));
+                        ui.end_row();
+                    }
 
-    match file.write_all(save_data.as_bytes()) {
-        Err(error_message) => println!("Unable to write to {}: {}", display, error_message),
-        Ok(_) => (),
-    }
-}
+                    ui.strong(translator.tr("total"));
+                    for hours in &report.tag_totals {
+                        ui.strong(duration_display.format(*hours));
+                    }
+                    ui.strong(duration_display.format(report.grand_total));
+                    ui.end_row();
+
+                    ui.label(translator.tr("earnings"));
+                    for earnings in &report.tag_earnings {
+                        match earnings {
+                            Some((amount, currency)) => ui.label(format!("{amount:.2} {currency}")),
+                            None => ui.label("-"),
+                        };
+                    }
+                    ui.end_row();
+                });
 
-fn load_from_file() -> SerializedTimeManager {
-    let path = Path::new("daily-time-keeper_save_data.toml");
-    let display = path.display();
+                ui.separator();
 
-    let mut file = File::open(&path).expect(&*format!("Unable to open {}", display));
+                Grid::new("report_billing_grid").show(ui, |ui| {
+                    ui.label(translator.tr("billable-hours"));
+                    ui.label(duration_display.format(report.billable_total));
+                    ui.end_row();
 
-    let mut serialized_time_manager = String::new();
-    match file.read_to_string(&mut serialized_time_manager) {
-        Err(error_message) => println!("Unable to read {}: {}", display, error_message),
-        Ok(_) => ()
-    };
+                    ui.label(translator.tr("non-billable-hours"));
+                    ui.label(duration_display.format(report.non_billable_total));
+                    ui.end_row();
 
-    toml::from_str(&*serialized_time_manager).unwrap()
-}
+                    for (currency, amount) in &report.earnings_per_currency {
+                        ui.label(translator.tr_args("earnings-in-currency", &[("currency", currency.as_str().into())]));
+                        ui.label(format!("{amount:.2}"));
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::thread::sleep;
-    use std::time::Duration;
-    use super::*;
+    fn show_invoices_window(&mut self, ctx: &Context) -> bool {
+        let mut is_changed = false;
+        let clients = billable_clients(&self.tags);
+        let rounding_scale = if self.is_rounding_on { Some(self.minute_rounding_scale) } else { None };
+        let mut is_invoices_open = self.is_invoices_open;
+        let translator = Rc::clone(&self.translator);
+
+        egui::Window::new(translator.tr("invoices")).id(Id::new("invoices_window")).open(&mut is_invoices_open).show(ctx, |ui| {
+            if clients.is_empty() {
+                ui.label(translator.tr("invoices-need-billable-tag"));
+                return;
+            }
+            if !clients.contains(&self.invoice_client) {
+                self.invoice_client.clone_from(&clients[0]);
+            }
+            if self.invoice_first_day_field.is_empty() {
+                let today = current_date();
+                self.invoice_first_day_field = self.clock_format.format_date(today.replace_day(1).unwrap());
+                self.invoice_last_day_field = self.clock_format.format_date(today);
+            }
+
+            Grid::new("invoice_options_grid").show(ui, |ui| {
+                ui.label(translator.tr("invoice-client"));
+                ComboBox::from_id_source("invoice_client")
+                    .selected_text(&self.invoice_client)
+                    .show_ui(ui, |ui| {
+                        for client in &clients {
+                            ui.selectable_value(&mut self.invoice_client, client.clone(), client);
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(translator.tr("invoice-from"));
+                ui.add(TextEdit::singleline(&mut self.invoice_first_day_field).hint_text(self.clock_format.date_format.label()).desired_width(90.));
+                ui.end_row();
+
+                ui.label(translator.tr("invoice-to"));
+                ui.add(TextEdit::singleline(&mut self.invoice_last_day_field).hint_text(self.clock_format.date_format.label()).desired_width(90.));
+                ui.end_row();
+
+                ui.label(translator.tr("line-items"));
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.invoice_grouping, InvoiceGrouping::PerSegment, translator.tr("per-segment"));
+                    ui.radio_value(&mut self.invoice_grouping, InvoiceGrouping::PerDay, translator.tr("per-day"));
+                });
+                ui.end_row();
+            });
 
-    #[test]
-    fn test_start_segment() {
-        let mut test_tag = Tag::new("test");
-        test_tag.start_time_segment(false, 0.0);
+            ui.separator();
+
+            let (Some(first_day), Some(last_day)) = (self.clock_format.parse_date(&self.invoice_first_day_field),
+                                                     self.clock_format.parse_date(&self.invoice_last_day_field)) else {
+                ui.label(translator.tr_args("enter-invoice-period", &[("format", self.clock_format.date_format.label().into())]));
+                return;
+            };
+
+            let invoice = Invoice::new(self.next_invoice_number, &self.tags, &self.invoice_client, first_day, last_day,
+                                       self.invoice_grouping, rounding_scale);
+            ui.label(translator.tr_args("invoice-summary", &[
+                ("number", invoice.number.into()),
+                ("line-count", invoice.lines.len().into()),
+                ("hours", self.duration_display.format(invoice.total_hours).into()),
+            ]));
+            for (currency, amount) in &invoice.totals_per_currency {
+                ui.label(translator.tr_args("amount-due", &[("amount", format!("{amount:.2}").into()), ("currency", currency.as_str().into())]));
+            }
+
+            if ui.add_enabled(!invoice.lines.is_empty(), Button::new(translator.tr("generate-invoice"))).clicked() {
+                let file_stem = invoice.file_stem();
+                save_to_file(Path::new(&format!("{file_stem}.html")), invoice.to_html().as_bytes());
+                save_to_file(Path::new(&format!("{file_stem}.pdf")), &invoice.to_pdf());
+                mark_invoiced(&mut self.tags, &invoice);
+                self.next_invoice_number += 1;
+                self.notifications.send(&translator.tr_args("invoice-created", &[("number", invoice.number.into())]),
+                                        &translator.tr_args("invoice-saved-to", &[
+                                            ("html-path", format!("{file_stem}.html").into()),
+                                            ("pdf-path", format!("{file_stem}.pdf").into()),
+                                        ]));
+
+                is_changed = true;
+            }
+        });
 
-        assert_eq!(test_tag.time_segments.len(), 1)
+        self.is_invoices_open = is_invoices_open;
+        is_changed
     }
 
-    #[test]
-    fn test_end_segment() {
+    fn show_archive_window(&mut self, ctx: &Context) -> bool {
+        let mut tag_to_be_unarchived = None;
+        let mut tag_to_be_purged = None;
+        let mut is_archive_open = self.is_archive_open;
+        let translator = &self.translator;
+
+        egui::Window::new(translator.tr("archived-tags")).id(Id::new("archived_tags_window")).open(&mut is_archive_open).show(ctx, |ui| {
+            let archived_tags: Vec<usize> = tag_order(&self.tags, false, TagSort::Manual).into_iter()
+                .map(|(tag_index, _)| tag_index)
+                .filter(|&tag_index| self.tags[tag_index].is_archived)
+                .collect();
+            if archived_tags.is_empty() {
+                ui.label(translator.tr("no-archived-tags"));
+            }
+
+            Grid::new("archived_tags_grid").show(ui, |ui| {
+                for tag_index in archived_tags {
+                    let tag = &self.tags[tag_index];
+                    let history_hours: f64 = tag.finished_segments().map(|segment| segment.hours_total).sum();
+                    ui.label(tag_path(&self.tags, tag_index));
+                    ui.label(translator.tr_args("archived-hours", &[("hours", self.duration_display.format(history_hours).into())]));
+
+                    if self.tag_pending_purge == Some(tag.id) {
+                        ui.colored_label(BUDGET_EXCEEDED_COLOR, translator.tr("confirm-purge"));
+                        if ui.add(Button::new(translator.tr("purge"))).clicked() {
+                            tag_to_be_purged = Some(tag_index);
+                        }
+                        if ui.add(Button::new(translator.tr("cancel"))).clicked() {
+                            self.tag_pending_purge = None;
+                        }
+                    } else {
+                        if ui.add(Button::new(translator.tr("unarchive"))).clicked() {
+                            tag_to_be_unarchived = Some(tag_index);
+                        }
+                        if ui.add(Button::new(translator.tr("purge"))).clicked() {
+                            self.tag_pending_purge = Some(tag.id);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        self.is_archive_open = is_archive_open;
+
+        if let Some(tag_index) = tag_to_be_unarchived {
+            set_archived(&mut self.tags, tag_index, false);
+        }
+        if let Some(tag_index) = tag_to_be_purged {
+            reparent_children(&mut self.tags, tag_index);
+            self.tags.remove(tag_index);
+            self.tag_pending_purge = None;
+        }
+
+        tag_to_be_unarchived.is_some() || tag_to_be_purged.is_some()
+    }
+
+    fn show_charts_window(&mut self, ctx: &Context) {
+        let now = current_time();
+        let today = now.date();
+
+        let translator = &self.translator;
+
+        egui::Window::new(translator.tr("charts")).id(Id::new("charts_window")).open(&mut self.is_charts_open).show(ctx, |ui| {
+            ui.label(translator.tr("today"));
+            let tag_names: Vec<String> = timeline_tags(&self.tags).map(Tag::display_name).collect();
+            Plot::new("timeline_plot")
+                .height(160.)
+                .include_x(0.)
+                .include_x(24.)
+                .legend(Legend::default())
+                .y_axis_formatter(move |value, _range| {
+                    let is_row = (value - value.round()).abs() < 0.01 && value >= 0.;
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let name = tag_names.get(value.round() as usize);
+                    match name {
+                        Some(name) if is_row => name.clone(),
+                        _ => String::new(),
+                    }
+                })
+                .show(ui, |plot_ui| {
+                    for chart in timeline_charts(&self.tags, today, now) {
+                        plot_ui.bar_chart(chart);
+                    }
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label(translator.tr("hours-per-day-weeks"));
+                ui.add(DragValue::new(&mut self.chart_week_count).clamp_range(1..=12));
+            });
+            let day_count = usize::from(self.chart_week_count) * 7;
+            let first_day = today - Duration::days(i64::from(self.chart_week_count) * 7 - 1);
+            Plot::new("daily_hours_plot")
+                .height(200.)
+                .include_y(0.)
+                .legend(Legend::default())
+                .x_axis_formatter(move |value, _range| {
+                    if value < 0. || (value - value.round()).abs() > 0.01 {
+                        return String::new();
+                    }
+                    #[allow(clippy::cast_possible_truncation)]
+                    let day = first_day + Duration::days(value.round() as i64);
+                    format!("{:02}-{:02}", u8::from(day.month()), day.day())
+                })
+                .show(ui, |plot_ui| {
+                    for chart in daily_hours_charts(&self.tags, first_day, day_count) {
+                        plot_ui.bar_chart(chart);
+                    }
+                });
+        });
+    }
+}
+
+impl TimeManager {
+    // Draws a frame and returns whether anything that is saved changed
+    #[allow(clippy::too_many_lines)]
+    fn show(&mut self, ctx: &Context) -> bool {
+        let mut tags_to_be_archived: Vec<usize> = Vec::new();
+        let mut tag_to_be_renamed: Option<usize> = None;
+        let mut is_changes_made = false;
+        let translator = Rc::clone(&self.translator);
+
+        if self.is_dark_mode {
+            ctx.set_visuals(Visuals::dark());
+        } else {
+            ctx.set_visuals(Visuals::light());
+        }
+
+        if self.handle_shortcuts(ctx) {
+            is_changes_made = true;
+        }
+        self.check_idle(ctx);
+        if self.check_pomodoro(current_time()) {
+            is_changes_made = true;
+        }
+        if self.check_reminders(current_time()) {
+            is_changes_made = true;
+        }
+        #[cfg(unix)]
+        if self.service_control_socket() {
+            is_changes_made = true;
+        }
+        #[cfg(feature = "http-api")]
+        if self.service_http_api() {
+            is_changes_made = true;
+        }
+
+        TopBottomPanel::top("Panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                // Adding new tag
+                let new_tag_response = ui.add(TextEdit::singleline( &mut self.tag_name)
+                    .id(Id::new(NEW_TAG_FIELD_ID))
+                    .hint_text(translator.tr("new-tag-hint")));
+                if new_tag_response.changed() {
+                    self.tag_name_error = None;
+                    self.highlighted_tag = None;
+                }
+                let is_enter_pressed = new_tag_response.lost_focus() && ui.input().key_pressed(Key::Enter);
+                let is_add_clicked = ui.add(Button::new(translator.tr("add-new-tag"))).clicked();
+                if (is_enter_pressed || is_add_clicked) && !self.tag_name.is_empty() {
+                    let tag_name = self.tag_name.clone();
+                    match self.add_tag(&tag_name) {
+                        Ok(()) => {
+                            self.tag_name.clear();
+
+                            is_changes_made = true;
+                        },
+                        Err(error) => self.tag_name_error = Some(error),
+                    }
+                }
+
+                if let Some(error) = &self.tag_name_error {
+                    ui.colored_label(BUDGET_EXCEEDED_COLOR, error.message(&translator));
+                } else {
+                    // Autocomplete from the existing names, archived ones included
+                    let suggestions = tag_name_suggestions(&self.tags, &self.tag_name, TAG_NAME_SUGGESTION_COUNT);
+                    if !suggestions.is_empty() {
+                        ui.label(translator.tr("existing-tags"));
+                    }
+                    for (tag_index, path, is_archived) in suggestions {
+                        let suggestion_text = if is_archived {
+                            translator.tr_args("archived-suggestion", &[("name", path.as_str().into())])
+                        } else {
+                            path
+                        };
+                        if ui.small_button(suggestion_text).clicked() {
+                            self.reveal_tag(tag_index);
+                            self.tag_name.clear();
+
+                            is_changes_made = true;
+                        }
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                // Rounding feature
+                ui.label(translator.tr("minute-rounding-scale"));
+                let minute_rounding_scale_response = ui.text_edit_singleline(&mut self.minute_rounding_scale_field);
+
+                if minute_rounding_scale_response.lost_focus() {
+                    match self.minute_rounding_scale_field.parse::<f32>() {
+                        Ok(user_rounding_scale) => {
+                            if (self.minute_rounding_scale - user_rounding_scale).abs() > f32::EPSILON {
+                                self.minute_rounding_scale = user_rounding_scale;
+
+                                is_changes_made = true;
+                            }
+                        },
+                        Err(_) => {
+                            self.minute_rounding_scale_field = self.minute_rounding_scale.to_string();
+                        },
+                    }
+                }
+
+                let rounding_enabled_response = ui.checkbox(&mut self.is_rounding_on, translator.tr("minute-rounding"));
+                if rounding_enabled_response.changed() {
+                    is_changes_made = true;
+                }
+
+                ui.separator();
+                let dark_mode_enabled_response = ui.checkbox(&mut self.is_dark_mode, translator.tr("dark-mode"));
+                if dark_mode_enabled_response.changed() {
+                    is_changes_made = true;
+                }
+
+                ui.separator();
+                ui.label(translator.tr("idle-after"));
+                let idle_minutes_response = ui.add(DragValue::new(&mut self.idle_minutes).clamp_range(0..=480).suffix(translator.tr("minutes-suffix")))
+                    .on_hover_text(translator.tr("idle-after-hint"));
+                if idle_minutes_response.changed() {
+                    is_changes_made = true;
+                }
+
+                ui.separator();
+                ui.label(translator.tr("durations"));
+                let previous_duration_display = self.duration_display;
+                ComboBox::from_id_source("duration_format")
+                    .selected_text(translator.tr(self.duration_display.format.label()))
+                    .show_ui(ui, |ui| {
+                        for duration_format in [DurationFormat::Decimal, DurationFormat::HoursMinutes, DurationFormat::HoursMinutesSeconds] {
+                            ui.selectable_value(&mut self.duration_display.format, duration_format, translator.tr(duration_format.label()));
+                        }
+                    });
+                if self.duration_display.format == DurationFormat::Decimal {
+                    ui.add(DragValue::new(&mut self.duration_display.precision).clamp_range(0..=6).suffix(translator.tr("decimal-places")));
+                }
+                if self.duration_display != previous_duration_display {
+                    is_changes_made = true;
+                }
+
+                ui.separator();
+                let previous_clock_format = self.clock_format;
+                ComboBox::from_id_source("hour_format")
+                    .selected_text(translator.tr(self.clock_format.hour_format.label()))
+                    .show_ui(ui, |ui| {
+                        for hour_format in [HourFormat::TwentyFourHour, HourFormat::TwelveHour] {
+                            ui.selectable_value(&mut self.clock_format.hour_format, hour_format, translator.tr(hour_format.label()));
+                        }
+                    });
+                ComboBox::from_id_source("date_format")
+                    .selected_text(self.clock_format.date_format.label())
+                    .show_ui(ui, |ui| {
+                        for date_format in [DateFormat::YearMonthDay, DateFormat::DayMonthYearDots, DateFormat::DayMonthYearSlashes,
+                                            DateFormat::MonthDayYear] {
+                            ui.selectable_value(&mut self.clock_format.date_format, date_format, date_format.label());
+                        }
+                    });
+                if self.clock_format != previous_clock_format {
+                    is_changes_made = true;
+                }
+
+                ui.separator();
+                ui.label(translator.tr("language"));
+                let previous_language = self.language;
+                ComboBox::from_id_source("language")
+                    .selected_text(self.language.map_or_else(|| translator.tr("language-system"), |language| language.native_name().to_owned()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.language, None, translator.tr("language-system"));
+                        for language in LANGUAGES {
+                            ui.selectable_value(&mut self.language, Some(language), language.native_name());
+                        }
+                    });
+                if self.language != previous_language {
+                    self.translator = Rc::new(Translator::new(self.language));
+                    is_changes_made = true;
+                }
+
+                #[cfg(feature = "http-api")]
+                {
+                    ui.separator();
+                    let http_api_response = ui.checkbox(&mut self.http_api.is_enabled, translator.tr("http-api"));
+                    let port_response = ui.add_enabled(self.http_api.is_enabled, DragValue::new(&mut self.http_api.port).clamp_range(1024..=65535))
+                        .on_hover_text(translator.tr("http-api-hint"));
+                    // The port only takes effect once it's no longer being dragged or typed
+                    let is_port_set = (port_response.drag_released() || port_response.lost_focus())
+                        && self.http_api_server.as_ref().is_none_or(|http_api_server| http_api_server.port() != self.http_api.port);
+                    if http_api_response.changed() || is_port_set {
+                        self.restart_http_api(ctx);
+                        is_changes_made = true;
+                    } else if port_response.changed() {
+                        is_changes_made = true;
+                    }
+                    if self.http_api.is_enabled && ui.button(translator.tr("copy-token")).clicked() {
+                        ui.output().copied_text.clone_from(&self.http_api.token);
+                    }
+                }
+
+                ui.separator();
+                if ui.add(Button::new(translator.tr("reports"))).clicked() {
+                    self.is_reports_open = !self.is_reports_open;
+                }
+                if ui.add(Button::new(translator.tr("charts"))).clicked() {
+                    self.is_charts_open = !self.is_charts_open;
+                }
+                if ui.add(Button::new(translator.tr("invoices"))).clicked() {
+                    self.is_invoices_open = !self.is_invoices_open;
+                }
+                if ui.add(Button::new(translator.tr("archived-tags"))).clicked() {
+                    self.is_archive_open = !self.is_archive_open;
+                }
+
+                ui.separator();
+                ui.label(translator.tr("sort-tags"));
+                let previous_tag_sort = self.tag_sort;
+                ComboBox::from_id_source("tag_sort")
+                    .selected_text(translator.tr(self.tag_sort.label()))
+                    .show_ui(ui, |ui| {
+                        for tag_sort in [TagSort::Manual, TagSort::Name, TagSort::RecentUse] {
+                            ui.selectable_value(&mut self.tag_sort, tag_sort, translator.tr(tag_sort.label()));
+                        }
+                    });
+                if self.tag_sort != previous_tag_sort {
+                    is_changes_made = true;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                // Filtering tags and time segments
+                ui.label(translator.tr("filter"));
+                ui.add(TextEdit::singleline(&mut self.filter_text)
+                    .id(Id::new(FILTER_FIELD_ID))
+                    .hint_text(translator.tr("filter-hint")));
+                ui.label(translator.tr("from"));
+                ui.add(TextEdit::singleline(&mut self.filter_first_day_field).hint_text(self.clock_format.date_format.label()).desired_width(80.));
+                ui.label(translator.tr("to"));
+                ui.add(TextEdit::singleline(&mut self.filter_last_day_field).hint_text(self.clock_format.date_format.label()).desired_width(80.));
+                if ui.add(Button::new(translator.tr("clear-filter"))).clicked() {
+                    self.filter_text.clear();
+                    self.filter_first_day_field.clear();
+                    self.filter_last_day_field.clear();
+                }
+            });
+        });
+
+        if self.check_budgets(current_time()) {
+            is_changes_made = true;
+        }
+        self.notifications.show(ctx);
+        if self.pomodoro_phase.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        } else if self.tags.iter().any(|tag| tag.is_active_segment) {
+            ctx.request_repaint_after(self.duration_display.refresh_interval());
+        } else if self.reminders.day_end_reminder_time.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_mins(1));
+        }
+        if self.show_command_palette(ctx) {
+            is_changes_made = true;
+        }
+        if self.show_idle_window(ctx) {
+            is_changes_made = true;
+        }
+        self.show_reports_window(ctx);
+        self.show_charts_window(ctx);
+        if self.show_invoices_window(ctx) {
+            is_changes_made = true;
+        }
+        if self.show_archive_window(ctx) {
+            is_changes_made = true;
+        }
+
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical().show(ui, |ui| {
+
+                let is_rounding_on = self.is_rounding_on;
+                let minute_rounding_scale = self.minute_rounding_scale;
+                let duration_display = self.duration_display;
+                let clock_format = self.clock_format;
+                let today = current_date();
+
+                let now = current_time();
+                let total_times: Vec<f64> = self.tags.iter().map(|tag| tag.running_total(now)).collect();
+                let rolled_up_total_times = rolled_up(&self.tags, &total_times);
+
+                let filter = TagFilter::new(&self.filter_text, &self.filter_first_day_field, &self.filter_last_day_field, self.clock_format);
+
+                let is_manual_sort = self.tag_sort == TagSort::Manual;
+                let mut tag_rows: Vec<(usize, Rect)> = Vec::new();
+                let mut drag_started_tag: Option<usize> = None;
+
+                for (tag_index, depth) in self.displayed_tags() {
+                    let is_tag_name_match = filter.is_name_match(&self.tags, tag_index);
+                    let filtered_history: Vec<String> = if filter.is_active() {
+                        self.tags[tag_index].history.iter()
+                            .filter(|segment| filter.matches_segment(&self.tags, tag_index, segment))
+                            .map(|segment| format_history_segment(segment, &translator, self.duration_display, self.clock_format))
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    let has_children = has_children(&self.tags, tag_index);
+                    let (parent_candidates, is_top_level_allowed) = if self.tags[tag_index].is_settings_open {
+                        (parent_candidates(&self.tags, tag_index),
+                         self.tags[tag_index].parent_id.is_none() || is_free_name_under(&self.tags, tag_index, None))
+                    } else {
+                        (Vec::new(), false)
+                    };
+                    #[allow(clippy::cast_precision_loss)]
+                    let indent = depth as f32 * 20.;
+                    let tag = &mut self.tags[tag_index];
+
+                    let tag_row_response = ui.horizontal(|ui| {
+                        ui.add_space(indent);
+                        if is_manual_sort {
+                            let drag_handle_response = ui.add(Label::new("☰").sense(Sense::drag()))
+                                .on_hover_text(translator.tr("drag-to-reorder"));
+                            if drag_handle_response.drag_started() {
+                                drag_started_tag = Some(tag_index);
+                            }
+                        }
+                        if has_children {
+                            let collapse_text = if tag.is_collapsed { "▶" } else { "▼" };
+                            if ui.small_button(collapse_text).clicked() {
+                                tag.is_collapsed = !tag.is_collapsed;
+
+                                is_changes_made = true;
+                            }
+                        }
+
+                        let button_text = if tag.is_active_segment { translator.tr("stop") } else { translator.tr("start") };
+
+                        if ui.add(Button::new(button_text)).clicked() {
+                            tag.toggle_time_segment(is_rounding_on, minute_rounding_scale);
+
+                            is_changes_made = true;
+                        }
+
+                        ui.separator();
+
+                        if tag.is_renaming {
+                            let rename_response = ui.add(TextEdit::singleline(&mut tag.rename_field).desired_width(120.));
+                            rename_response.request_focus();
+                            if ui.input().key_pressed(Key::Escape) {
+                                tag.is_renaming = false;
+                            } else if rename_response.lost_focus() {
+                                if tag.rename_field.trim() != tag.name {
+                                    tag_to_be_renamed = Some(tag_index);
+                                }
+                                tag.is_renaming = false;
+                            }
+                        } else {
+                            let name_text = RichText::new(tag.display_name()).color(tag_color(tag));
+                            let name_response = ui.add(Label::new(name_text).sense(Sense::click()))
+                                .on_hover_text(translator.tr("double-click-to-rename"));
+                            if name_response.double_clicked() {
+                                tag.rename_field.clone_from(&tag.name);
+                                tag.is_renaming = true;
+                            }
+                        }
+                        let pomodoro_count = completed_pomodoros(tag, today);
+                        if pomodoro_count > 0 {
+                            ui.label(format!("🍅 {pomodoro_count}"))
+                                .on_hover_text(translator.tr("completed-pomodoros"));
+                        }
+                        ui.separator();
+                        let budget_state = tag.budget.map(|budget| {
+                            let budget_hours = tag.hours_since(budget.period.first_day(today), now);
+                            (budget, budget_hours, budget.is_exceeded(budget_hours))
+                        });
+                        let is_cap_exceeded = matches!(budget_state, Some((Budget { kind: BudgetKind::Cap, .. }, _, true)));
+
+                        let total_hours_text = if has_children {
+                            translator.tr_args("total-hours-with-subtags", &[
+                                ("hours", duration_display.format(total_times[tag_index]).into()),
+                                ("rolled-up-hours", duration_display.format(rolled_up_total_times[tag_index]).into()),
+                            ])
+                        } else {
+                            translator.tr_args("total-hours", &[("hours", duration_display.format(total_times[tag_index]).into())])
+                        };
+                        if is_cap_exceeded {
+                            ui.colored_label(BUDGET_EXCEEDED_COLOR, total_hours_text);
+                        } else {
+                            ui.label(total_hours_text);
+                        }
+
+                        if tag.billing.is_billable {
+                            ui.separator();
+                            let billing_rounding_scale = if is_rounding_on { Some(minute_rounding_scale) } else { None };
+                            ui.label(translator.tr_args("earnings-amount", &[
+                                ("amount", format!("{:.2}", tag.session_earnings(billing_rounding_scale)).into()),
+                                ("currency", tag.billing.currency.as_str().into()),
+                            ]));
+                        }
+
+                        if let Some((budget, budget_hours, is_exceeded)) = budget_state {
+                            ui.scope(|ui| {
+                                if is_exceeded {
+                                    ui.visuals_mut().selection.bg_fill = match budget.kind {
+                                        BudgetKind::Target => BUDGET_REACHED_COLOR,
+                                        BudgetKind::Cap => BUDGET_EXCEEDED_COLOR,
+                                    };
+                                }
+                                let progress_text = format!("{} / {} {}", duration_display.format(budget_hours),
+                                                            duration_display.format(budget.hours), translator.tr(budget.period.label()));
+                                ui.add(ProgressBar::new(budget.progress(budget_hours)).desired_width(140.).text(progress_text));
+                            });
+                        }
+
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
+                            if ui.add(Button::new(translator.tr("archive-tag"))).clicked() {
+                                tags_to_be_archived.push(tag_index);
+
+                                is_changes_made = true;
+                            }
+                            if ui.add(Button::new(translator.tr("settings"))).clicked() {
+                                tag.is_settings_open = !tag.is_settings_open;
+                            }
+                            let pin_text = if tag.is_pinned { translator.tr("unpin") } else { translator.tr("pin") };
+                            if ui.add(Button::new(pin_text)).clicked() {
+                                tag.is_pinned = !tag.is_pinned;
+
+                                is_changes_made = true;
+                            }
+                        });
+
+                    });
+                    tag_rows.push((tag_index, tag_row_response.response.rect));
+                    if self.highlighted_tag == Some(tag.id) {
+                        ui.painter().rect_stroke(tag_row_response.response.rect.expand(2.), 2., ui.visuals().selection.stroke);
+                        if self.is_highlight_scroll_pending {
+                            tag_row_response.response.scroll_to_me(Some(Align::Center));
+                            self.is_highlight_scroll_pending = false;
+                        }
+                    }
+
+                    if tag.is_settings_open && show_tag_settings(ui, &translator, tag, &parent_candidates, is_top_level_allowed, indent) {
+                        is_changes_made = true;
+                    }
+
+                    let mut segments_to_be_deleted: Vec<usize> = Vec::new();
+                    ui.vertical(|ui| {
+                        for history_line in &filtered_history {
+                            ui.horizontal(|ui| {
+                                ui.add_space(40f32 + indent);
+                                ui.weak(history_line);
+                            });
+                        }
+
+                        let tag_id = tag.id;
+                        for (segment_index, segment) in tag.time_segments.iter_mut().enumerate() {
+                            // Checked here rather than ahead of the row since Start adds a segment in the same frame
+                            if filter.is_active() && !filter.matches_tag_segment(is_tag_name_match, segment) {
+                                continue;
+                            }
+                            // Invoiced segments are locked so the invoice keeps matching them
+                            let is_invoiced = segment.invoice_number.is_some();
+                            ui.horizontal(|ui| {
+                                ui.add_space(40f32 + indent);
+                                let start_time_id = Id::new(("segment_start_time", tag_id, segment_index));
+                                if !ui.memory().has_focus(start_time_id) {
+                                    segment.start_time_field = clock_format.format_time(segment.start_time.unwrap());
+                                }
+                                let start_time_response = ui.add(TextEdit::singleline(&mut segment.start_time_field)
+                                    .id(start_time_id)
+                                    .interactive(!is_invoiced)
+                                    .desired_width(TIME_FIELD_WIDTH));
+                                if start_time_response.lost_focus() {
+                                    if let Some(user_time) = clock_format.parse_time(&segment.start_time_field) {
+                                        segment.start_time = Some(segment.start_time.unwrap().replace_time(user_time));
+                                        if segment.end_time.is_some() {
+                                            segment.calculate_total_hours();
+                                        }
+
+                                        is_changes_made = true;
+                                    }
+                                }
+
+                                ui.add_space(20.);
+                                ui.label("-");
+                                ui.add_space(20.);
+
+                                if let Some(end_time) = segment.end_time {
+                                    let end_time_id = Id::new(("segment_end_time", tag_id, segment_index));
+                                    if !ui.memory().has_focus(end_time_id) {
+                                        segment.end_time_field = clock_format.format_time(end_time);
+                                    }
+                                    let end_time_response = ui.add(TextEdit::singleline(&mut segment.end_time_field)
+                                        .id(end_time_id)
+                                        .interactive(!is_invoiced)
+                                        .desired_width(TIME_FIELD_WIDTH));
+                                    if end_time_response.lost_focus() {
+                                        if let Some(user_time) = clock_format.parse_time(&segment.end_time_field) {
+                                            segment.end_time = Some(end_time.replace_time(user_time));
+                                            segment.calculate_total_hours();
+
+                                            is_changes_made = true;
+                                        }
+                                    }
+                                }
+                                ui.separator();
+                                ui.label(translator.tr_args("segment-hours", &[("hours", duration_display.format(segment.elapsed_hours(now)).into())]));
+
+                                ui.separator();
+                                let note_response = ui.add(TextEdit::singleline(&mut segment.note)
+                                    .hint_text(translator.tr("note-hint"))
+                                    .interactive(!is_invoiced)
+                                    .desired_width(160.));
+                                if note_response.lost_focus() {
+                                    is_changes_made = true;
+                                }
+                                if let Some(invoice_number) = segment.invoice_number {
+                                    ui.label(translator.tr_args("invoiced", &[("number", invoice_number.into())]));
+                                }
+
+                                ui.add_space(20.);
+                                // ui.separator();
+
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
+                                    if ui.add_enabled(!is_invoiced, Button::new(translator.tr("remove-time-segment"))).clicked() {
+                                        segments_to_be_deleted.push(segment_index);
+                                    }
+                                });
+                            });
+                        }
+
+                        for segment_index in segments_to_be_deleted.drain(..) {
+                            if tag.time_segments.len() - 1 == segment_index {
+                                tag.is_active_segment = false;
+                            }
+
+                            tag.time_segments.remove(segment_index);
+
+                            is_changes_made = true;
+                        }
+
+                        tag.calculate_total();
+
+                        ui.separator();
+                    });
+                }
+
+                ui.add_space(20.);
+
+                if let Some(tag_index) = tag_to_be_renamed {
+                    match validate_tag_name(&self.tags, &self.tags[tag_index].rename_field, self.tags[tag_index].parent_id, Some(tag_index)) {
+                        Ok(new_name) => {
+                            self.tags[tag_index].name = new_name;
+
+                            is_changes_made = true;
+                        },
+                        Err(error) => self.notifications.send(&translator.tr("tag-not-renamed"), &error.message(&translator)),
+                    }
+                }
+
+                for tag_index in tags_to_be_archived.drain(..) {
+                    set_archived(&mut self.tags, tag_index, true);
+                }
+
+                for tag in &mut self.tags {
+                    if tag.is_archived && tag.is_active_segment {
+                        tag.end_time_segment(is_rounding_on, minute_rounding_scale);
+                        tag.calculate_total();
+                    }
+                }
+
+                if drag_started_tag.is_some() {
+                    self.dragged_tag = drag_started_tag;
+                }
+                if let Some(dragged_tag) = self.dragged_tag.filter(|&tag_index| tag_index < self.tags.len()) {
+                    // Tags can only be dropped among their siblings, the hierarchy is changed in the tag settings
+                    let pointer_position = ui.input().pointer.hover_pos();
+                    let drop_target = pointer_position.and_then(|position| {
+                        tag_rows.iter().find(|(tag_index, rect)| {
+                            *tag_index != dragged_tag
+                                && self.tags[*tag_index].parent_id == self.tags[dragged_tag].parent_id
+                                && rect.y_range().contains(&position.y)
+                        })
+                    });
+
+                    if let Some((target_tag, target_rect)) = drop_target {
+                        let indicator_y = if *target_tag < dragged_tag { target_rect.top() } else { target_rect.bottom() };
+                        ui.painter().hline(target_rect.x_range(), indicator_y, ui.visuals().selection.stroke);
+                    }
+
+                    if ui.input().pointer.any_released() {
+                        if let Some(&(target_tag, _)) = drop_target {
+                            move_tag(&mut self.tags, dragged_tag, target_tag);
+
+                            is_changes_made = true;
+                        }
+                        self.dragged_tag = None;
+                    }
+                }
+            });
+        });
+
+        TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.add(Button::new(translator.tr("clear-session"))).clicked() {
+                    for tag in &mut self.tags {
+                        tag.clear_session();
+                    }
+
+                    is_changes_made = true;
+                }
+                // ui.separator();
+                // ui.label("End session & save");
+
+                ui.separator();
+                let today = current_date();
+                let today_hours = worked_hours_per_day(&self.tags).get(&today).copied().unwrap_or(0.0);
+                ui.label(translator.tr_args("today-hours", &[
+                    ("hours", self.duration_display.format(today_hours).into()),
+                    ("expected-hours", self.duration_display.format(self.work_schedule.expected_hours_on(today)).into()),
+                ]));
+                ui.separator();
+                let balance = self.work_schedule.balance(&self.tags, today);
+                let balance_sign = if balance < 0.0 { "" } else { "+" };
+                let balance_text = translator.tr_args("balance", &[("hours", format!("{balance_sign}{}", self.duration_display.format(balance)).into())]);
+                if balance < 0.0 {
+                    ui.colored_label(BUDGET_EXCEEDED_COLOR, balance_text);
+                } else {
+                    ui.label(balance_text);
+                }
+                if ui.add(Button::new(translator.tr("work-hours"))).clicked() {
+                    self.is_work_schedule_open = !self.is_work_schedule_open;
+                }
+
+                ui.separator();
+                let now = current_time();
+                ui.label(translator.tr_args("break-time", &[("hours", self.duration_display.format(break_hours_on(&self.breaks, today, now)).into())]));
+                let is_on_break = self.breaks.last().is_some_and(|segment| segment.end_time.is_none());
+                if !is_on_break && ui.add(Button::new(translator.tr("start-break"))).clicked() {
+                    self.run_command(ctx, Command::StopAll);
+                    self.breaks.push(TimeSegment::new(self.is_rounding_on, self.minute_rounding_scale));
+
+                    is_changes_made = true;
+                }
+                if is_on_break && ui.add(Button::new(translator.tr("end-break"))).clicked() {
+                    self.end_break(now);
+                    self.pomodoro_phase = None;
+
+                    is_changes_made = true;
+                }
+                let work_day = WorkDay::new(&self.tags, &self.breaks, today, now, self.break_rules.minimum_break_minutes);
+                for violation in self.break_rules.violations(&work_day) {
+                    let warning = match violation {
+                        BreakViolation::TooLittleBreak { worked_hours, required_minutes, taken_minutes } => translator.tr_args("break-too-short", &[
+                            ("hours", worked_hours.into()),
+                            ("required-minutes", required_minutes.into()),
+                            ("taken-minutes", taken_minutes.into()),
+                        ]),
+                        BreakViolation::TooLongWithoutBreak { hours } => translator.tr_args("break-too-late", &[("hours", hours.into())]),
+                    };
+                    ui.colored_label(BUDGET_EXCEEDED_COLOR, format!("⚠ {warning}"));
+                }
+
+                ui.separator();
+                if ui.checkbox(&mut self.pomodoro.is_enabled, translator.tr("pomodoro")).changed() {
+                    is_changes_made = true;
+                }
+                if self.pomodoro.is_enabled {
+                    ui.label(translator.tr("pomodoro-work"));
+                    is_changes_made |= ui.add(DragValue::new(&mut self.pomodoro.work_minutes).clamp_range(1..=180).suffix(translator.tr("minutes-suffix"))).changed();
+                    ui.label(translator.tr("pomodoro-break"));
+                    is_changes_made |= ui.add(DragValue::new(&mut self.pomodoro.break_minutes).clamp_range(1..=60).suffix(translator.tr("minutes-suffix"))).changed();
+                }
+                if let Some(phase) = self.pomodoro_phase {
+                    let seconds_left = (phase.ends_at() - current_time()).whole_seconds().max(0);
+                    let time_left = format!("{}:{:02}", seconds_left / 60, seconds_left % 60);
+                    let phase_text = match phase {
+                        PomodoroPhase::Work { tag_id, .. } => {
+                            let tag_name = self.tags.iter().find(|tag| tag.id == tag_id).map_or(String::new(), Tag::display_name);
+                            translator.tr_args("pomodoro-work-left", &[("tag", tag_name.into()), ("time", time_left.into())])
+                        },
+                        PomodoroPhase::Break { .. } => translator.tr_args("pomodoro-break-left", &[("time", time_left.into())]),
+                    };
+                    ui.strong(phase_text);
+                }
+            });
+        });
+
+        if self.show_work_schedule_window(ctx) {
+            is_changes_made = true;
+        }
+
+        // Save state to file
+        is_changes_made
+    }
+}
+
+impl App for TimeManager {
+    fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+        if self.show(ctx) {
+            let serialized_time_manager = self.to_serialized();
+            let time_manager_as_toml = to_toml_string(&serialized_time_manager);
+            save_to_file(&save_file_path(), time_manager_as_toml.as_bytes());
+        }
+    }
+}
+
+// Past segments are only listed while filtering, they can't be edited from the main list
+fn format_history_segment(segment: &TimeSegment, translator: &Translator, duration_display: DurationDisplay, clock_format: ClockFormat) -> String {
+    let start_time = segment.start_time.unwrap();
+    let end_time_text = segment.end_time.map_or(String::new(), |end_time| clock_format.format_time(end_time));
+    let mut text = translator.tr_args("history-segment", &[
+        ("date", clock_format.format_date(start_time.date()).into()),
+        ("start", clock_format.format_time(start_time).into()),
+        ("end", end_time_text.into()),
+        ("hours", duration_display.format(segment.hours_total).into()),
+    ]);
+    if !segment.note.is_empty() {
+        text.push_str("  ");
+        text.push_str(&translator.tr_args("history-segment-note", &[("note", segment.note.as_str().into())]));
+    }
+
+    text
+}
+
+fn show_tag_settings(ui: &mut Ui, translator: &Translator, tag: &mut Tag, parent_candidates: &[(u32, String)], is_top_level_allowed: bool,
+                     indent: f32) -> bool {
+    let mut is_changed = false;
+
+    ui.horizontal(|ui| {
+        ui.add_space(40. + indent);
+        ui.label(translator.tr("color"));
+        let color = tag_color(tag);
+        let mut rgb = [color.r(), color.g(), color.b()];
+        if ui.color_edit_button_srgb(&mut rgb).changed() {
+            tag.color = Some(rgb);
+            is_changed = true;
+        }
+        if tag.color.is_some() && ui.small_button(translator.tr("default-color")).clicked() {
+            tag.color = None;
+            is_changed = true;
+        }
+
+        ui.label(translator.tr("icon"));
+        let icon_response = ui.add(TextEdit::singleline(&mut tag.icon).hint_text(translator.tr("icon-hint")).desired_width(40.));
+        if icon_response.lost_focus() {
+            let icon = tag.icon.trim().to_owned();
+            tag.icon = icon;
+            is_changed = true;
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add_space(40. + indent);
+        ui.label(translator.tr("parent"));
+        let parent_name = parent_candidates.iter()
+            .find(|(candidate_id, _)| Some(*candidate_id) == tag.parent_id)
+            .map_or_else(|| translator.tr("no-parent"), |(_, path)| path.clone());
+        ComboBox::from_id_source(("tag_parent", tag.id))
+            .selected_text(parent_name)
+            .show_ui(ui, |ui| {
+                // Left out when a top-level tag already has the name
+                if is_top_level_allowed {
+                    is_changed |= ui.selectable_value(&mut tag.parent_id, None, translator.tr("no-parent")).changed();
+                }
+                for (candidate_id, path) in parent_candidates {
+                    is_changed |= ui.selectable_value(&mut tag.parent_id, Some(*candidate_id), path).changed();
+                }
+            });
+    });
+
+    ui.horizontal(|ui| {
+        ui.add_space(40. + indent);
+        ui.label(translator.tr("budget"));
+
+        let mut period = tag.budget.map(|budget| budget.period);
+        ComboBox::from_id_source(("budget_period", tag.id))
+            .selected_text(translator.tr(period.map_or("no-budget", BudgetPeriod::label)))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut period, None, translator.tr("no-budget"));
+                ui.selectable_value(&mut period, Some(BudgetPeriod::Daily), translator.tr(BudgetPeriod::Daily.label()));
+                ui.selectable_value(&mut period, Some(BudgetPeriod::Weekly), translator.tr(BudgetPeriod::Weekly.label()));
+            });
+
+        match (period, tag.budget.as_mut()) {
+            (None, Some(_)) => {
+                tag.budget = None;
+                is_changed = true;
+            },
+            (Some(period), None) => {
+                tag.budget = Some(Budget::new(period));
+                is_changed = true;
+            },
+            (Some(period), Some(budget)) => {
+                if budget.period != period {
+                    budget.period = period;
+                    is_changed = true;
+                }
+            },
+            (None, None) => (),
+        }
+
+        if let Some(budget) = tag.budget.as_mut() {
+            ui.label(translator.tr("budget-hours"));
+            is_changed |= ui.add(DragValue::new(&mut budget.hours).speed(0.25).clamp_range(0.0..=168.0)).changed();
+            is_changed |= ui.radio_value(&mut budget.kind, BudgetKind::Target, translator.tr("budget-target")).changed();
+            is_changed |= ui.radio_value(&mut budget.kind, BudgetKind::Cap, translator.tr("budget-cap")).changed();
+            is_changed |= ui.checkbox(&mut budget.is_notification_on, translator.tr("budget-notify")).changed();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add_space(40. + indent);
+        is_changed |= ui.checkbox(&mut tag.billing.is_billable, translator.tr("billable")).changed();
+        if tag.billing.is_billable {
+            ui.label(translator.tr("hourly-rate"));
+            is_changed |= ui.add(DragValue::new(&mut tag.billing.hourly_rate).speed(1.0).clamp_range(0.0..=f64::MAX)).changed();
+            let currency_response = ui.add(TextEdit::singleline(&mut tag.billing.currency).desired_width(40.));
+            is_changed |= currency_response.lost_focus();
+            ui.label(translator.tr("client"));
+            let client_response = ui.add(TextEdit::singleline(&mut tag.billing.client).desired_width(120.));
+            is_changed |= client_response.lost_focus();
+        }
+    });
+
+    if tag.billing.is_billable {
+        ui.horizontal(|ui| {
+            ui.add_space(40. + indent);
+            is_changed |= show_billing_rounding(ui, translator, &mut tag.billing, tag.id);
+        });
+    }
+
+    is_changed
+}
+
+fn show_billing_rounding(ui: &mut Ui, translator: &Translator, billing: &mut Billing, tag_id: u32) -> bool {
+    let mut is_changed = false;
+
+    ui.label(translator.tr("billing-rounding"));
+    ComboBox::from_id_source(("billing_rounding", tag_id))
+        .selected_text(translator.tr(billing.rounding.label()))
+        .show_ui(ui, |ui| {
+            for rounding in [BillingRounding::Global, BillingRounding::Off, BillingRounding::Own] {
+                is_changed |= ui.selectable_value(&mut billing.rounding, rounding, translator.tr(rounding.label())).changed();
+            }
+        });
+    if billing.rounding == BillingRounding::Own {
+        is_changed |= ui.add(DragValue::new(&mut billing.own_rounding_scale).speed(0.05).clamp_range(0.01..=1.0)).changed();
+    }
+
+    is_changed
+}
+
+// Shows the time in the clock format unless it's being typed in, which takes effect once the field loses focus
+fn time_of_day_field(ui: &mut Ui, id: Id, field: &mut String, time_of_day: &mut Time, clock_format: ClockFormat) -> bool {
+    if !ui.memory().has_focus(id) {
+        *field = clock_format.format_time(current_time().replace_time(*time_of_day));
+    }
+
+    let response = ui.add(TextEdit::singleline(field).id(id).desired_width(TIME_FIELD_WIDTH));
+    match clock_format.parse_time(field) {
+        Some(user_time) if response.lost_focus() && user_time != *time_of_day => {
+            *time_of_day = user_time;
+            true
+        },
+        _ => false,
+    }
+}
+
+// `daily-time-keeper ctl start Writing` sends the rest of the command line to the running instance
+#[cfg(unix)]
+fn run_control_client(arguments: &[String]) -> i32 {
+    match send_request(&control_socket_path(), &request_line(arguments)) {
+        Ok(Ok(reply)) => {
+            print!("{reply}");
+            0
+        },
+        Ok(Err(message)) => {
+            eprintln!("{message}");
+            1
+        },
+        Err(error_message) => {
+            eprintln!("Unable to reach a running daily-time-keeper: {error_message}");
+            1
+        },
+    }
+}
+
+#[cfg(not(unix))]
+fn run_control_client(_arguments: &[String]) -> i32 {
+    eprintln!("The control socket is only available on Unix");
+    1
+}
+
+// `daily-time-keeper status [--watch] [--format text|waybar]` for status bars. It reads the save file, which the window
+// rewrites whenever a tag starts or stops, so it works whether the window is open or not
+fn run_status(arguments: &[String]) -> i32 {
+    let mut is_watching = false;
+    let mut format = StatusFormat::Text;
+    let mut path = save_file_path();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--watch" => is_watching = true,
+            "--format" => {
+                let Some(chosen_format) = arguments.next().and_then(|name| StatusFormat::from_name(name)) else {
+                    eprintln!("--format expects text or waybar");
+                    return 1;
+                };
+                format = chosen_format;
+            },
+            "--file" => {
+                let Some(file) = arguments.next() else {
+                    eprintln!("--file expects the path of a save file");
+                    return 1;
+                };
+                path = PathBuf::from(file);
+            },
+            _ => {
+                eprintln!("Unknown argument {argument}, expected --watch, --format text|waybar or --file <path>");
+                return 1;
+            },
+        }
+    }
+
+    let mut previous_output: Option<String> = None;
+    let mut is_missing_reported = false;
+    loop {
+        if !path.exists() {
+            if !is_missing_reported {
+                eprintln!("There is no save file at {}, start the window once or pass --file", path.display());
+                is_missing_reported = true;
+            }
+            if !is_watching {
+                return 1;
+            }
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            continue;
+        }
+
+        let output = read_save_file(&path).map(|save_data| {
+            let tags: Vec<Tag> = save_data.tags.iter().map(Tag::from_serialized).collect();
+            status_output(&running_tags(&tags), current_time(), save_data.duration_display, save_data.clock_format, format)
+        });
+
+        if !is_watching {
+            let Some(output) = output else {
+                eprintln!("Unable to read {}", path.display());
+                return 1;
+            };
+            println!("{output}");
+            return 0;
+        }
+
+        // Kept as it was while the window is in the middle of writing the file
+        if let Some(output) = output.filter(|output| previous_output.as_ref() != Some(output)) {
+            println!("{output}");
+            previous_output = Some(output);
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+
+fn first_invoice_number() -> u32 {
+    1
+}
+
+fn current_time() -> OffsetDateTime {
+    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc())
+}
+
+fn current_date() -> time::Date {
+    current_time().date()
+}
+
+// Going through `toml::Value` emits plain values ahead of tables regardless of the field order of the struct
+fn to_toml_string<T: Serialize>(data: &T) -> String {
+    toml::to_string(&toml::Value::try_from(data).unwrap()).unwrap()
+}
+
+// In the user's data directory so the window and `status` find it whatever their working directory is
+fn save_file_path() -> PathBuf {
+    let data_directory = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+            .filter(|data_home| data_home.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share")))
+    };
+
+    data_directory.map_or_else(|| PathBuf::from(SAVE_FILE_NAME), |data_directory| data_directory.join("daily-time-keeper").join(SAVE_FILE_NAME))
+}
+
+fn save_to_file(path: &Path, save_data: &[u8]) {
+    let display = path.display();
+    if let Some(directory) = path.parent().filter(|directory| !directory.as_os_str().is_empty()) {
+        std::fs::create_dir_all(directory).ok();
+    }
+
+    let mut file = File::create(path).unwrap_or_else(|_| panic!("Unable to create {display}"));
+
+    if let Err(error_message) = file.write_all(save_data) {
+        println!("Unable to write to {display}: {error_message}");
+    }
+}
+
+fn load_from_file(path: &Path) -> SerializedTimeManager {
+    let display = path.display();
+
+    let mut file = File::open(path).unwrap_or_else(|_| panic!("Unable to open {display}"));
+
+    let mut serialized_time_manager = String::new();
+    if let Err(error_message) = file.read_to_string(&mut serialized_time_manager) {
+        println!("Unable to read {display}: {error_message}");
+    }
+
+    toml::from_str(&serialized_time_manager).unwrap()
+}
+
+// Unlike `load_from_file` this doesn't panic on a missing or half written file
+fn read_save_file(path: &Path) -> Option<SerializedTimeManager> {
+    toml::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+    use time::Time;
+    use time::macros::{datetime, time};
+    use crate::pdf::*;
+    use crate::time_segment::*;
+    use super::*;
+
+    #[test]
+    #[allow(clippy::semicolon_if_nothing_returned)]
+    fn test_start_segment() {
+        let mut test_tag = Tag::new("test");
+        test_tag.start_time_segment(false, 0.0);
+
+        assert_eq!(test_tag.time_segments.len(), 1)
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn test_end_segment() {
         let mut test_tag = Tag::new("test");
         test_tag.start_time_segment(false, 0.0);
         test_tag.end_time_segment(false, 0.0);
@@ -433,14 +2417,13 @@ mod tests {
     #[test]
     fn test_calculate_total_hours() {
         let mut test_tag = Tag::new("test");
-        test_tag.start_time_segment(false, 0.0);
-        sleep(Duration::from_secs(5));
-        test_tag.end_time_segment(false, 0.0);
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00:00 UTC), Some(datetime!(2022-03-07 9:00:05 UTC))));
 
-        assert_eq!(test_tag.time_segments.last().unwrap().hours_total, 0.001388888888888889);
+        assert!((test_tag.time_segments.last().unwrap().hours_total - 5.0 / 3600.0).abs() < f64::EPSILON);
     }
 
     #[test]
+    #[allow(clippy::explicit_iter_loop)]
     fn test_time_rounding() {
         let time_manager = TimeSegment::new(false, 0.0);
         let rounding_scale = 0.25;
@@ -463,4 +2446,810 @@ mod tests {
             assert_eq!(Time::from_hms(rounded_time.0, rounded_time.1, 0), time[1]);
         }
     }
+
+    #[test]
+    fn test_report_aggregation() {
+        let mut first_tag = Tag::new("first");
+        first_tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:30 UTC))));
+        first_tag.history.push(TimeSegment::from_times(datetime!(2022-03-08 9:00 UTC), Some(datetime!(2022-03-08 9:10 UTC))));
+        first_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-14 9:00 UTC), None));
+        let mut second_tag = Tag::new("second");
+        second_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-13 13:00 UTC), Some(datetime!(2022-03-13 15:00 UTC))));
+        let tags = [first_tag, second_tag];
+
+        let daily_report = Report::new(&tags, ReportOptions { period: ReportPeriod::Day, rounding_scale: None, billing_rounding_scale: None, is_rolled_up: false });
+        assert_eq!(daily_report.rows.len(), 3);
+        assert_eq!(daily_report.rows[0].label, "2022-03-07");
+
+        let weekly_report = Report::new(&tags, ReportOptions { period: ReportPeriod::Week, rounding_scale: None, billing_rounding_scale: None, is_rolled_up: false });
+        assert_eq!(weekly_report.rows.len(), 1);
+        assert_eq!(weekly_report.rows[0].label, "2022-W10");
+        assert!((weekly_report.tag_totals[0] - 1.666_666).abs() < 0.001);
+        assert!((weekly_report.grand_total - 3.666_666).abs() < 0.001);
+
+        let rounded_report = Report::new(&tags, ReportOptions { period: ReportPeriod::Month, rounding_scale: Some(0.25), billing_rounding_scale: None, is_rolled_up: false });
+        assert_eq!(rounded_report.rows[0].label, "2022-03");
+        assert!((rounded_report.tag_totals[0] - 1.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_segments_survive_serialization() {
+        let mut test_tag = Tag::new("test");
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC))));
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 11:00 UTC), None));
+        test_tag.is_active_segment = true;
+        test_tag.clear_session();
+
+        let tag_as_toml = to_toml_string(&test_tag.to_serialized());
+        let loaded_tag = Tag::from_serialized(&toml::from_str(&tag_as_toml).unwrap());
+
+        // The running segment isn't dropped but keeps running in the new session
+        assert_eq!(loaded_tag.time_segments.len(), 1);
+        assert!(loaded_tag.is_active_segment);
+        assert_eq!(loaded_tag.history.len(), 1);
+        assert!((loaded_tag.history[0].hours_total - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_daily_hours() {
+        let mut test_tag = Tag::new("test");
+        test_tag.history.push(TimeSegment::from_times(datetime!(2022-03-06 9:00 UTC), Some(datetime!(2022-03-06 10:00 UTC))));
+        test_tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC))));
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-09 9:00 UTC), Some(datetime!(2022-03-09 11:30 UTC))));
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-09 12:00 UTC), None));
+
+        let hours_per_day = daily_hours(&test_tag, datetime!(2022-03-07 0:00 UTC).date(), 3);
+        assert_eq!(hours_per_day.len(), 3);
+        assert!((hours_per_day[0] - 1.0).abs() < f64::EPSILON);
+        assert!(hours_per_day[1].abs() < f64::EPSILON);
+        assert!((hours_per_day[2] - 2.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_weekly_budget_hours() {
+        let mut test_tag = Tag::new("test");
+        test_tag.budget = Some(Budget { hours: 2.0, period: BudgetPeriod::Weekly, kind: BudgetKind::Cap, is_notification_on: false });
+        test_tag.history.push(TimeSegment::from_times(datetime!(2022-03-06 9:00 UTC), Some(datetime!(2022-03-06 12:00 UTC))));
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-08 9:00 UTC), Some(datetime!(2022-03-08 10:30 UTC))));
+
+        let budget = test_tag.budget.unwrap();
+        let first_day = budget.period.first_day(datetime!(2022-03-10 0:00 UTC).date());
+        assert_eq!(first_day, datetime!(2022-03-07 0:00 UTC).date());
+
+        let now = datetime!(2022-03-10 12:00 UTC);
+        let budget_hours = test_tag.hours_since(first_day, now);
+        assert!((budget_hours - 1.5).abs() < f64::EPSILON);
+        assert!(!budget.is_exceeded(budget_hours));
+        assert!((budget.progress(budget_hours) - 0.75).abs() < f32::EPSILON);
+
+        // The running segment counts towards the budget and the warning is only sent once per period, restarts included
+        test_tag.budget = Some(Budget { is_notification_on: true, ..budget });
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-10 11:00 UTC), None));
+        assert!((test_tag.hours_since(first_day, now) - 2.5).abs() < f64::EPSILON);
+        let mut time_manager = TimeManager::new();
+        time_manager.tags.push(test_tag);
+        assert!(time_manager.check_budgets(now));
+        assert_eq!(time_manager.tags[0].budget_warning_sent_at, Some(now));
+        let mut time_manager = TimeManager::new_from_serialized(&time_manager.to_serialized());
+        assert!(!time_manager.check_budgets(datetime!(2022-03-10 13:00 UTC)));
+        assert!(time_manager.check_budgets(datetime!(2022-03-14 12:00 UTC)));
+        assert_eq!(time_manager.tags[0].budget_warning_sent_at, None);
+    }
+
+    #[test]
+    fn test_save_data_round_trip() {
+        let mut time_manager = TimeManager::new();
+        let mut test_tag = Tag::new("test");
+        test_tag.budget = Some(Budget::new(BudgetPeriod::Daily));
+        test_tag.icon = "📚".to_owned();
+        test_tag.color = Some([10, 20, 30]);
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC))));
+        test_tag.history.push(TimeSegment::from_times(datetime!(2022-03-06 9:00 UTC), Some(datetime!(2022-03-06 10:00 UTC))));
+        time_manager.tags.push(test_tag);
+        time_manager.tags.push(Tag::new("second"));
+
+        let time_manager_as_toml = to_toml_string(&time_manager.to_serialized());
+        let loaded_time_manager = TimeManager::new_from_serialized(&toml::from_str(&time_manager_as_toml).unwrap());
+
+        assert_eq!(loaded_time_manager.tags.len(), 2);
+        assert_eq!(loaded_time_manager.tags[0].time_segments.len(), 1);
+        assert_eq!(loaded_time_manager.tags[0].history.len(), 1);
+        assert!(loaded_time_manager.tags[0].budget.is_some());
+        assert_eq!(loaded_time_manager.tags[0].display_name(), "📚 test");
+        assert_eq!(tag_color(&loaded_time_manager.tags[0]), Color32::from_rgb(10, 20, 30));
+    }
+
+    #[test]
+    fn test_overtime_balance() {
+        let mut test_tag = Tag::new("test");
+        // Friday with 6 hours expected, Saturday and Sunday with none, Monday with 8
+        test_tag.history.push(TimeSegment::from_times(datetime!(2022-03-04 9:00 UTC), Some(datetime!(2022-03-04 16:00 UTC))));
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 16:30 UTC))));
+        let tags = [test_tag];
+
+        let mut work_schedule = WorkSchedule::default();
+        work_schedule.expected_hours[4] = 6.0;
+
+        // Monday's half hour short only counts once Monday is over
+        assert!((work_schedule.balance(&tags, datetime!(2022-03-07 0:00 UTC).date()) - 1.0).abs() < f64::EPSILON);
+        assert!((work_schedule.balance(&tags, datetime!(2022-03-08 0:00 UTC).date()) - 0.5).abs() < f64::EPSILON);
+        assert!((work_schedule.balance(&tags, datetime!(2022-03-09 0:00 UTC).date()) + 7.5).abs() < f64::EPSILON);
+        work_schedule.expected_hours[0] = 7.0;
+        assert!((work_schedule.balance(&tags, datetime!(2022-03-07 0:00 UTC).date()) - 1.5).abs() < f64::EPSILON);
+        assert!(work_schedule.balance(&[], datetime!(2022-03-08 0:00 UTC).date()).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_billable_report_totals() {
+        let mut billable_tag = Tag::new("client, inc");
+        billable_tag.billing = Billing { is_billable: true, hourly_rate: 100.0, currency: "EUR".to_owned(), client: String::new(), ..Billing::default() };
+        billable_tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:10 UTC))));
+        let mut internal_tag = Tag::new("internal");
+        internal_tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 11:00 UTC), Some(datetime!(2022-03-07 11:30 UTC))));
+        let mut tags = [billable_tag, internal_tag];
+
+        let report = Report::new(&tags, ReportOptions { period: ReportPeriod::Day, rounding_scale: None, billing_rounding_scale: Some(0.25), is_rolled_up: false });
+        assert!((report.non_billable_total - 0.5).abs() < f64::EPSILON);
+        assert!((report.billable_total - 1.166_666).abs() < 0.001);
+        assert!((report.earnings_per_currency["EUR"] - 125.0).abs() < f64::EPSILON);
+        assert!(report.tag_earnings[1].is_none());
+
+        let csv = report_to_csv(&report, DurationDisplay::default());
+        assert!(csv.starts_with("Period,\"client, inc\",internal,Total\n"));
+        assert!(csv.contains("Earnings (EUR),125.00\n"));
+
+        // A tag's own rounding overrides the global one
+        tags[0].billing.rounding = BillingRounding::Off;
+        let report = Report::new(&tags, ReportOptions { period: ReportPeriod::Day, rounding_scale: None, billing_rounding_scale: Some(0.25), is_rolled_up: false });
+        assert!((report.earnings_per_currency["EUR"] - 116.666).abs() < 0.001);
+        tags[0].billing.rounding = BillingRounding::Own;
+        tags[0].billing.own_rounding_scale = 0.5;
+        let report = Report::new(&tags, ReportOptions { period: ReportPeriod::Day, rounding_scale: None, billing_rounding_scale: None, is_rolled_up: false });
+        assert!((report.earnings_per_currency["EUR"] - 100.0).abs() < f64::EPSILON);
+        let billing: Billing = toml::from_str(&toml::to_string(&tags[0].billing).unwrap()).unwrap();
+        assert!(billing.rounding == BillingRounding::Own && (billing.own_rounding_scale - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_invoice_marks_segments() {
+        let mut billable_tag = Tag::new("design");
+        billable_tag.billing = Billing { is_billable: true, hourly_rate: 80.0, currency: "USD".to_owned(), client: "Acme".to_owned(), ..Billing::default() };
+        billable_tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC))));
+        billable_tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 13:00 UTC), Some(datetime!(2022-03-07 13:30 UTC))));
+        billable_tag.history[1].note = "Logo <draft>".to_owned();
+        billable_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-04-01 9:00 UTC), Some(datetime!(2022-04-01 10:00 UTC))));
+        let mut tags = [billable_tag];
+
+        let first_day = parse_date("2022-03-01").unwrap();
+        let last_day = parse_date("2022-03-31").unwrap();
+        let invoice = Invoice::new(7, &tags, "Acme", first_day, last_day, InvoiceGrouping::PerDay, None);
+        assert_eq!(invoice.lines.len(), 1);
+        assert_eq!(invoice.lines[0].description, "design: Logo <draft>");
+        assert!((invoice.totals_per_currency["USD"] - 120.0).abs() < f64::EPSILON);
+        assert!(invoice.to_html().contains("Logo &lt;draft&gt;"));
+        assert!(invoice.to_pdf().starts_with(b"%PDF-1.4"));
+
+        mark_invoiced(&mut tags, &invoice);
+        assert_eq!(tags[0].history[1].invoice_number, Some(7));
+        assert_eq!(tags[0].time_segments[0].invoice_number, None);
+        assert!(Invoice::new(8, &tags, "Acme", first_day, last_day, InvoiceGrouping::PerSegment, None).lines.is_empty());
+    }
+
+    #[test]
+    fn test_pdf_pages() {
+        let lines: Vec<String> = (0..120).map(|line_number| format!("Line (#{line_number})")).collect();
+        let pdf = String::from_utf8(text_to_pdf(&lines)).unwrap();
+
+        assert!(pdf.contains("/Count 3"));
+        assert!(pdf.contains("(Line \\(#0\\)) '"));
+        assert!(pdf.ends_with("%%EOF\n"));
+
+        let pdf = String::from_utf8(text_to_pdf(&["Größe: 12 € – 日".to_owned()])).unwrap();
+        assert!(pdf.contains("/Encoding /WinAnsiEncoding"));
+        assert!(pdf.contains("(Gr\\366\\337e: 12 \\200 \\226 ?) '"));
+    }
+
+    #[test]
+    fn test_tag_hierarchy() {
+        let mut tags = Vec::new();
+        for (id, parent_id, name) in [(1, None, "Acme"), (2, Some(4), "Design"), (3, None, "Internal"), (4, Some(1), "Website")] {
+            let mut tag = Tag::new(name);
+            tag.id = id;
+            tag.parent_id = parent_id;
+            tag.history.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC))));
+            tags.push(tag);
+        }
+
+        let order: Vec<(usize, usize)> = tag_order(&tags, true, TagSort::Manual);
+        assert_eq!(order, vec![(0, 0), (3, 1), (1, 2), (2, 0)]);
+        assert_eq!(tag_path(&tags, 1), "Acme / Website / Design");
+        assert_eq!(rolled_up(&tags, &[1.0, 1.0, 1.0, 1.0]), vec![3.0, 1.0, 1.0, 2.0]);
+        assert_eq!(parent_candidates(&tags, 3).iter().map(|(id, _)| *id).collect::<Vec<u32>>(), vec![1, 3]);
+
+        tags[0].is_collapsed = true;
+        assert_eq!(tag_order(&tags, true, TagSort::Manual), vec![(0, 0), (2, 0)]);
+
+        let report = Report::new(&tags, ReportOptions { period: ReportPeriod::Day, rounding_scale: None, billing_rounding_scale: None, is_rolled_up: true });
+        assert_eq!(report.tag_names[0], "Acme");
+        assert!((report.tag_totals[0] - 3.0).abs() < f64::EPSILON);
+        assert!((report.grand_total - 4.0).abs() < f64::EPSILON);
+
+        reparent_children(&mut tags, 3);
+        tags.remove(3);
+        assert_eq!(tags[1].parent_id, Some(1));
+    }
+
+    #[test]
+    fn test_archive_tags() {
+        let mut tags = Vec::new();
+        for (id, parent_id, name) in [(1, None, "Acme"), (2, Some(1), "Website"), (3, None, "Internal")] {
+            let mut tag = Tag::new(name);
+            tag.id = id;
+            tag.parent_id = parent_id;
+            tags.push(tag);
+        }
+
+        set_archived(&mut tags, 0, true);
+        assert!(tags[1].is_archived);
+        assert_eq!(tag_order(&tags, true, TagSort::Manual), vec![(2, 0)]);
+        assert_eq!(tag_order(&tags, false, TagSort::Manual).len(), 3);
+
+        set_archived(&mut tags, 1, false);
+        assert!(!tags[0].is_archived);
+        assert_eq!(tag_order(&tags, true, TagSort::Manual), vec![(0, 0), (1, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn test_tag_sorting() {
+        let mut tags = Vec::new();
+        for (id, name, start_time) in [(1, "writing", datetime!(2022-03-07 9:00 UTC)), (2, "Admin", datetime!(2022-03-08 9:00 UTC)),
+                                       (3, "coding", datetime!(2022-03-06 9:00 UTC))] {
+            let mut tag = Tag::new(name);
+            tag.id = id;
+            tag.history.push(TimeSegment::from_times(start_time, Some(start_time + time::Duration::hours(1))));
+            tags.push(tag);
+        }
+
+        assert_eq!(tag_order(&tags, true, TagSort::Manual), vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(tag_order(&tags, true, TagSort::Name), vec![(1, 0), (2, 0), (0, 0)]);
+        assert_eq!(tag_order(&tags, true, TagSort::RecentUse), vec![(1, 0), (0, 0), (2, 0)]);
+
+        tags[2].is_pinned = true;
+        assert_eq!(tag_order(&tags, true, TagSort::RecentUse), vec![(2, 0), (1, 0), (0, 0)]);
+
+        move_tag(&mut tags, 0, 2);
+        assert_eq!(tags.iter().map(|tag| tag.id).collect::<Vec<u32>>(), vec![2, 3, 1]);
+        move_tag(&mut tags, 2, 0);
+        assert_eq!(tags.iter().map(|tag| tag.id).collect::<Vec<u32>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let mut tags = Vec::new();
+        for (id, parent_id, name) in [(1, None, "Acme"), (2, Some(1), "Website"), (3, None, "Internal")] {
+            let mut tag = Tag::new(name);
+            tag.id = id;
+            tag.parent_id = parent_id;
+            tags.push(tag);
+        }
+        let mut segment = TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC)));
+        segment.note = "Fixed the Login page".to_owned();
+        tags[1].history.push(segment);
+        tags[2].time_segments.push(TimeSegment::from_times(datetime!(2022-03-09 9:00 UTC), Some(datetime!(2022-03-09 10:00 UTC))));
+
+        let iso_clock = ClockFormat::from_locale("C");
+        assert_eq!(TagFilter::new("", "", "", iso_clock).visible_tags(&tags), vec![true, true, true]);
+        assert_eq!(TagFilter::new("login", "", "", iso_clock).visible_tags(&tags), vec![true, true, false]);
+        assert_eq!(TagFilter::new("inter", "", "", iso_clock).visible_tags(&tags), vec![false, false, true]);
+        assert_eq!(TagFilter::new("", "2022-03-08", "", iso_clock).visible_tags(&tags), vec![false, false, true]);
+        assert_eq!(TagFilter::new("internal", "2022-03-01", "2022-03-08", iso_clock).visible_tags(&tags), vec![false, false, false]);
+
+        let filter = TagFilter::new("acme", "", "2022-03-07", iso_clock);
+        assert!(filter.matches_segment(&tags, 1, &tags[1].history[0]));
+    }
+
+    #[test]
+    fn test_start_while_filtering() {
+        fn find_text(shapes: &[egui::epaint::ClippedShape], text: &str) -> Option<egui::Pos2> {
+            fn find_in(shape: &egui::Shape, text: &str) -> Option<egui::Pos2> {
+                match shape {
+                    egui::Shape::Text(text_shape) if text_shape.galley.text() == text => Some(text_shape.galley.rect.translate(text_shape.pos.to_vec2()).center()),
+                    egui::Shape::Vec(shapes) => shapes.iter().find_map(|shape| find_in(shape, text)),
+                    _ => None,
+                }
+            }
+            shapes.iter().find_map(|clipped_shape| find_in(&clipped_shape.1, text))
+        }
+
+        let mut time_manager = TimeManager::new();
+        time_manager.translator = Rc::new(Translator::new(Some(Language::English)));
+        time_manager.is_rounding_on = false;
+        time_manager.add_tag("Writing").unwrap();
+        time_manager.filter_text = "writ".to_owned();
+
+        let ctx = Context::default();
+        let mut run_frame = |events: Vec<egui::Event>| {
+            let raw_input = egui::RawInput { events, ..egui::RawInput::default() };
+            let mut is_changed = false;
+            let output = ctx.run(raw_input, |ctx| is_changed = time_manager.show(ctx));
+            (output, is_changed)
+        };
+
+        let (output, _) = run_frame(Vec::new());
+        let start_position = find_text(&output.shapes, "Start").unwrap();
+        run_frame(vec![
+            egui::Event::PointerMoved(start_position),
+            egui::Event::PointerButton { pos: start_position, button: egui::PointerButton::Primary, pressed: true, modifiers: Modifiers::NONE },
+        ]);
+        let (_, is_changed) = run_frame(vec![
+            egui::Event::PointerButton { pos: start_position, button: egui::PointerButton::Primary, pressed: false, modifiers: Modifiers::NONE },
+        ]);
+        assert!(is_changed);
+        let (output, _) = run_frame(Vec::new());
+
+        assert!(time_manager.tags[0].is_active_segment);
+        assert!(find_text(&output.shapes, "Stop").is_some());
+    }
+
+    #[test]
+    fn test_command_palette_matching() {
+        assert!(fuzzy_score("rpt", "Open Reports").is_some());
+        assert!(fuzzy_score("stpo", "Stop All Tags").is_none());
+        assert!(fuzzy_score("rep", "Open Reports") > fuzzy_score("rep", "Stop Website Prep"));
+
+        let mut tags = Vec::new();
+        for (id, name) in [(1, "Website"), (2, "Writing")] {
+            let mut tag = Tag::new(name);
+            tag.id = id;
+            tags.push(tag);
+        }
+        tags[1].start_time_segment(false, 0.25);
+
+        let translator = Translator::new(Some(Language::English));
+        let commands = matching_commands(&tags, &translator, "stop wri");
+        assert!(commands[0].0 == Command::ToggleTag(2));
+        assert_eq!(commands[0].1, "Stop Writing");
+        assert!(matching_commands(&tags, &translator, "").len() <= PALETTE_RESULT_COUNT);
+
+        let mut time_manager = TimeManager::new();
+        time_manager.tags = tags;
+        assert!(time_manager.run_command(&Context::default(), Command::StopAll));
+        assert!(time_manager.tags.iter().all(|tag| !tag.is_active_segment));
+    }
+
+    #[test]
+    fn test_tag_name_validation() {
+        let mut time_manager = TimeManager::new();
+        assert!(time_manager.add_tag("  Website ").is_ok());
+        assert_eq!(time_manager.tags[0].name, "Website");
+        assert!(time_manager.add_tag("website").is_err());
+        assert!(time_manager.add_tag("   ").is_err());
+
+        time_manager.add_tag("Writing").unwrap();
+        time_manager.tags[1].is_archived = true;
+        assert_eq!(time_manager.add_tag("WRITING"), Err(TagNameError::TakenByArchived("Writing".to_owned())));
+        assert_eq!(validate_tag_name(&time_manager.tags, "writing ", None, Some(1)), Ok("writing".to_owned()));
+
+        let suggestions = tag_name_suggestions(&time_manager.tags, "it", 5);
+        assert_eq!(suggestions, vec![(0, "Website".to_owned(), false), (1, "Writing".to_owned(), true)]);
+        assert_eq!(tag_name_suggestions(&time_manager.tags, "wr", 5), vec![(1, "Writing".to_owned(), true)]);
+
+        // Only siblings need different names
+        time_manager.add_tag("Client A").unwrap();
+        time_manager.add_tag("Meetings").unwrap();
+        time_manager.tags[3].parent_id = Some(time_manager.tags[2].id);
+        assert!(time_manager.add_tag("Meetings").is_ok());
+        assert!(validate_tag_name(&time_manager.tags, "meetings", Some(time_manager.tags[2].id), None).is_err());
+        assert!(parent_candidates(&time_manager.tags, 4).iter().all(|(id, _)| *id != time_manager.tags[2].id));
+        assert!(!is_free_name_under(&time_manager.tags, 3, None));
+
+        // Picking a suggestion brings the existing tag back instead of filling in a name that is taken
+        time_manager.filter_text = "client".to_owned();
+        time_manager.reveal_tag(1);
+        assert!(!time_manager.tags[1].is_archived);
+        assert!(time_manager.filter_text.is_empty());
+        assert_eq!(time_manager.highlighted_tag, Some(time_manager.tags[1].id));
+    }
+
+    #[test]
+    fn test_duration_display() {
+        let hours = 1.0 + 5.0 / 60.0 + 30.0 / 3600.0;
+        assert_eq!(DurationDisplay::default().format(hours), "1.09");
+        assert_eq!(DurationDisplay { format: DurationFormat::Decimal, precision: 0 }.format(hours), "1");
+        assert_eq!(DurationDisplay { format: DurationFormat::HoursMinutes, precision: 2 }.format(hours), "1:06");
+        assert_eq!(DurationDisplay { format: DurationFormat::HoursMinutesSeconds, precision: 2 }.format(hours), "1:05:30");
+        assert_eq!(DurationDisplay { format: DurationFormat::HoursMinutes, precision: 2 }.format(-0.5), "-0:30");
+        assert_eq!(DurationDisplay { format: DurationFormat::HoursMinutesSeconds, precision: 2 }.format(0.001_388_888_9), "0:00:05");
+    }
+
+    #[test]
+    fn test_running_total() {
+        let mut test_tag = Tag::new("test");
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 10:00 UTC))));
+        test_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 11:00 UTC), None));
+
+        assert!((test_tag.running_total(datetime!(2022-03-07 11:30 UTC)) - 1.5).abs() < f64::EPSILON);
+        assert!((test_tag.running_total(datetime!(2022-03-07 10:45 UTC)) - 1.0).abs() < f64::EPSILON);
+
+        assert_eq!(DurationDisplay::default().refresh_interval(), std::time::Duration::from_secs(36));
+        assert_eq!(DurationDisplay { format: DurationFormat::Decimal, precision: 0 }.refresh_interval(), std::time::Duration::from_mins(1));
+    }
+
+    #[test]
+    fn test_clock_format() {
+        let us_clock = ClockFormat::from_locale("en_US.UTF-8");
+        let german_clock = ClockFormat::from_locale("de_DE.UTF-8@euro");
+        assert!(us_clock.hour_format == HourFormat::TwelveHour && us_clock.date_format == DateFormat::MonthDayYear);
+        assert!(german_clock.hour_format == HourFormat::TwentyFourHour && german_clock.date_format == DateFormat::DayMonthYearDots);
+
+        let time_stamp = datetime!(2022-03-07 21:05 UTC);
+        assert_eq!(german_clock.format_time(time_stamp), "21:05");
+        assert_eq!(german_clock.format_time(datetime!(2022-03-07 9:05 UTC)), "09:05");
+        assert_eq!(us_clock.format_time(time_stamp), "9:05 PM");
+        assert_eq!(us_clock.format_time(datetime!(2022-03-07 0:30 UTC)), "12:30 AM");
+
+        for clock_format in [us_clock, german_clock] {
+            assert_eq!(clock_format.parse_time(&clock_format.format_time(time_stamp)), Some(time_stamp.time()));
+            assert_eq!(clock_format.parse_date(&clock_format.format_date(time_stamp.date())), Some(time_stamp.date()));
+            assert_eq!(clock_format.parse_date("2022-03-07"), Some(time_stamp.date()));
+        }
+        assert_eq!(us_clock.parse_time("12 am"), Some(time!(0:00)));
+        assert_eq!(us_clock.parse_time("13:00 pm"), None);
+        assert_eq!(us_clock.parse_time("9:75"), None);
+        assert_eq!(us_clock.format_date(time_stamp.date()), "03/07/2022");
+        assert_eq!(german_clock.parse_date("31.02.2022"), None);
+    }
+
+    #[test]
+    fn test_translation() {
+        assert!(Language::from_locale("de_AT.UTF-8") == Language::German);
+        assert!(Language::from_locale("en_US.UTF-8") == Language::English);
+        assert!(Language::from_locale("C") == Language::English);
+
+        let english = Translator::new(Some(Language::English));
+        let german = Translator::new(Some(Language::German));
+        assert_eq!(english.tr("stop"), "Stop");
+        assert_eq!(german.tr("stop"), "Stopp");
+        assert_eq!(german.tr_args("total-hours", &[("hours", "1.50".into())]), "Stunden gesamt: 1.50");
+        assert_eq!(english.tr_args("invoice-summary", &[("number", 3.into()), ("line-count", 1.into()), ("hours", "2.00".into())]),
+                   "Invoice 3: 1 line item, 2.00 hours");
+        assert_eq!(german.tr(WEEKDAY_NAMES[0]), "Montag");
+        assert_eq!(german.tr("not-a-message"), "not-a-message");
+        assert_eq!(TagNameError::Taken("Website".to_owned()).message(&german), "Es gibt bereits einen Tag namens „Website“");
+    }
+
+    #[test]
+    fn test_idle_time() {
+        let mut idle_tracker = IdleTracker::new(datetime!(2022-03-07 9:00 UTC));
+        let threshold = time::Duration::minutes(10);
+        assert_eq!(idle_tracker.check(datetime!(2022-03-07 9:05 UTC), threshold), None);
+        assert_eq!(idle_tracker.check(datetime!(2022-03-07 9:30 UTC), threshold), None);
+        idle_tracker.record_input(datetime!(2022-03-07 9:45 UTC));
+        assert_eq!(idle_tracker.check(datetime!(2022-03-07 9:45 UTC), threshold),
+                   Some((datetime!(2022-03-07 9:00 UTC), datetime!(2022-03-07 9:45 UTC))));
+
+        // Work in other windows keeps the desktop's idle time low
+        // and counts from when it was queried rather than when the answer was picked up
+        idle_tracker.record_system_idle(Some(time::Duration::minutes(1)), datetime!(2022-03-07 10:29 UTC), datetime!(2022-03-07 10:30 UTC));
+        assert_eq!(idle_tracker.idle_time(datetime!(2022-03-07 10:32 UTC)), time::Duration::minutes(4));
+
+        // Failed queries are retried less and less often instead of giving up
+        assert_eq!(system_idle_poll_interval(0), time::Duration::seconds(30));
+        assert_eq!(system_idle_poll_interval(2), time::Duration::minutes(2));
+        assert_eq!(system_idle_poll_interval(u32::MAX), time::Duration::minutes(30));
+
+        let mut tag = Tag::new("Website");
+        tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 8:00 UTC), None));
+        tag.is_active_segment = true;
+        let removed_hours = tag.cut_interval(datetime!(2022-03-07 9:00 UTC), datetime!(2022-03-07 9:45 UTC));
+        assert!((removed_hours - 0.75).abs() < f64::EPSILON);
+        assert_eq!(tag.time_segments.len(), 2);
+        assert_eq!(tag.time_segments[0].end_time, Some(datetime!(2022-03-07 9:00 UTC)));
+        assert_eq!(tag.time_segments[1].start_time, Some(datetime!(2022-03-07 9:45 UTC)));
+        assert!(tag.time_segments[1].end_time.is_none());
+
+        tag.insert_segment(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), Some(datetime!(2022-03-07 9:45 UTC))));
+        assert_eq!(tag.time_segments[1].start_time, Some(datetime!(2022-03-07 9:00 UTC)));
+        assert!(tag.time_segments.last().unwrap().end_time.is_none());
+        assert!((tag.total_time - 1.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_pomodoro() {
+        let mut time_manager = TimeManager::new();
+        time_manager.pomodoro.is_enabled = true;
+        time_manager.add_tag("Website").unwrap();
+        time_manager.tags[0].time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), None));
+        time_manager.tags[0].is_active_segment = true;
+
+        assert!(!time_manager.check_pomodoro(datetime!(2022-03-07 9:00 UTC)));
+        assert!(!time_manager.check_pomodoro(datetime!(2022-03-07 9:20 UTC)));
+        assert!(time_manager.check_pomodoro(datetime!(2022-03-07 9:26 UTC)));
+        let tag = &time_manager.tags[0];
+        assert!(!tag.is_active_segment);
+        assert_eq!(tag.time_segments[0].end_time, Some(datetime!(2022-03-07 9:25 UTC)));
+        assert_eq!(completed_pomodoros(tag, datetime!(2022-03-07 9:00 UTC).date()), 1);
+        assert!(time_manager.pomodoro_phase == Some(PomodoroPhase::Break { ends_at: datetime!(2022-03-07 9:30 UTC) }));
+
+        // The break is logged apart from the tag's total
+        assert!(time_manager.check_pomodoro(datetime!(2022-03-07 9:31 UTC)));
+        assert!(time_manager.pomodoro_phase.is_none());
+        assert!((time_manager.breaks[0].hours_total - 5.0 / 60.0).abs() < 1e-9);
+        assert!((time_manager.tags[0].total_time - 25.0 / 60.0).abs() < 1e-9);
+
+        // Only the pomodoro's tag is stopped and the interval counts from its start, even when noticed late
+        time_manager.add_tag("Admin").unwrap();
+        time_manager.tags[1].time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:40 UTC), None));
+        time_manager.tags[1].is_active_segment = true;
+        time_manager.tags[0].time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:45 UTC), None));
+        time_manager.tags[0].is_active_segment = true;
+        assert!(!time_manager.check_pomodoro(datetime!(2022-03-07 9:50 UTC)));
+        assert!(time_manager.pomodoro_phase == Some(PomodoroPhase::Work { tag_id: time_manager.tags[1].id, ends_at: datetime!(2022-03-07 10:05 UTC) }));
+        assert!(time_manager.check_pomodoro(datetime!(2022-03-07 10:06 UTC)));
+        assert!(!time_manager.tags[1].is_active_segment);
+        assert!(time_manager.tags[0].is_active_segment);
+
+        // Turning the mode off during the break ends it
+        time_manager.pomodoro.is_enabled = false;
+        assert!(time_manager.check_pomodoro(datetime!(2022-03-07 10:08 UTC)));
+        assert!(time_manager.pomodoro_phase.is_none());
+        assert_eq!(time_manager.breaks[1].end_time, Some(datetime!(2022-03-07 10:08 UTC)));
+    }
+
+    #[test]
+    fn test_break_rules() {
+        let mut tags = vec![Tag::new("Website"), Tag::new("Admin")];
+        tags[0].history.push(TimeSegment::from_times(datetime!(2022-03-07 8:00 UTC), Some(datetime!(2022-03-07 12:00 UTC))));
+        tags[0].history.push(TimeSegment::from_times(datetime!(2022-03-07 12:20 UTC), Some(datetime!(2022-03-07 15:00 UTC))));
+        // Parallel tags count once and pauses under the minimum are no break
+        tags[1].history.push(TimeSegment::from_times(datetime!(2022-03-07 14:00 UTC), Some(datetime!(2022-03-07 15:05 UTC))));
+        tags[1].time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 15:10 UTC), None));
+        let now = datetime!(2022-03-07 16:10 UTC);
+
+        let work_day = WorkDay::new(&tags, &[], now.date(), now, 15);
+        assert!((work_day.worked_hours - (4.0 + 2.75 + 1.0)).abs() < 1e-9);
+        assert_eq!(work_day.break_minutes, 20);
+        assert!((work_day.longest_stretch_hours - 4.0).abs() < 1e-9);
+
+        let mut break_rules = BreakRules::default();
+        assert!(break_rules.violations(&work_day).is_empty());
+        break_rules.is_enabled = true;
+        assert_eq!(break_rules.violations(&work_day),
+                   vec![BreakViolation::TooLittleBreak { worked_hours: 6.0, required_minutes: 30, taken_minutes: 20 }]);
+        break_rules.max_hours_without_break = 3.5;
+        assert_eq!(break_rules.violations(&work_day).len(), 2);
+
+        // A break logged while the tag kept running is taken out of the worked time and splits the stretch
+        let mut running_tag = Tag::new("Website");
+        running_tag.time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 8:00 UTC), None));
+        let breaks = [TimeSegment::from_times(datetime!(2022-03-07 12:00 UTC), Some(datetime!(2022-03-07 12:30 UTC)))];
+        let work_day = WorkDay::new(&[running_tag], &breaks, now.date(), now, 15);
+        assert!((work_day.worked_hours - 7.666_666_666_666_667).abs() < 1e-9);
+        assert_eq!(work_day.break_minutes, 30);
+        assert!((work_day.longest_stretch_hours - 4.0).abs() < 1e-9);
+        break_rules.max_hours_without_break = 6.0;
+        assert!(break_rules.violations(&work_day).is_empty());
+    }
+
+    #[test]
+    fn test_reminders() {
+        assert_eq!(next_time_of_day(datetime!(2022-03-07 9:00 UTC), time!(18:00)), datetime!(2022-03-07 18:00 UTC));
+        assert_eq!(next_time_of_day(datetime!(2022-03-07 19:00 UTC), time!(18:00)), datetime!(2022-03-08 18:00 UTC));
+
+        let mut time_manager = TimeManager::new();
+        time_manager.reminders.long_run_hours = Some(4.0);
+        time_manager.add_tag("Website").unwrap();
+        time_manager.tags[0].time_segments.push(TimeSegment::from_times(datetime!(2022-03-07 9:00 UTC), None));
+        time_manager.tags[0].is_active_segment = true;
+        assert!(!time_manager.check_reminders(datetime!(2022-03-07 14:00 UTC)));
+        assert!(time_manager.tags[0].is_long_run_warning_sent);
+
+        // Left running overnight
+        time_manager.reminders.auto_stop_time = Some(time!(18:00));
+        assert!(time_manager.check_reminders(datetime!(2022-03-08 8:00 UTC)));
+        assert!(!time_manager.tags[0].is_active_segment);
+        assert!(!time_manager.tags[0].is_long_run_warning_sent);
+        assert!((time_manager.tags[0].total_time - 9.0).abs() < f64::EPSILON);
+    }
+
+    // Runs its own bus with dbus-daemon, skipped where that isn't installed
+    #[cfg(feature = "desktop-notifications")]
+    #[test]
+    fn test_desktop_notifications() {
+        use std::collections::HashMap;
+        use std::io::{BufRead, BufReader};
+        use std::process::{Command, Stdio};
+        use std::sync::{Arc, Mutex};
+        use zbus::blocking::connection::Builder;
+        use crate::desktop_notification::*;
+
+        struct NotificationDaemon {
+            received: Arc<Mutex<Vec<(String, String)>>>,
+        }
+
+        #[zbus::interface(name = "org.freedesktop.Notifications")]
+        impl NotificationDaemon {
+            #[allow(unused_variables, clippy::too_many_arguments, clippy::needless_pass_by_value)]
+            fn notify(&self, app_name: &str, replaces_id: u32, app_icon: &str, summary: &str, body: &str, actions: Vec<&str>,
+                      hints: HashMap<&str, zbus::zvariant::Value<'_>>, expire_timeout: i32) -> u32 {
+                self.received.lock().unwrap().push((summary.to_owned(), body.to_owned()));
+                1
+            }
+        }
+
+        let Ok(mut dbus_daemon) = Command::new("dbus-daemon").args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn() else {
+            println!("dbus-daemon isn't installed");
+            return;
+        };
+        let mut address = String::new();
+        BufReader::new(dbus_daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        let address = address.trim();
+
+        let client = Builder::address(address).unwrap().build().unwrap();
+        assert!(DesktopNotifier::with_connection(client).is_none());
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let _daemon_connection = Builder::address(address).unwrap()
+            .name(NOTIFICATIONS_NAME).unwrap()
+            .serve_at(NOTIFICATIONS_PATH, NotificationDaemon { received: Arc::clone(&received) }).unwrap()
+            .build().unwrap();
+        let client = Builder::address(address).unwrap().build().unwrap();
+        let desktop_notifier = DesktopNotifier::with_connection(client).unwrap();
+        assert!(desktop_notifier.send("Invoice 7 created", "Saved to invoice-00007.html"));
+        assert_eq!(*received.lock().unwrap(), vec![("Invoice 7 created".to_owned(), "Saved to invoice-00007.html".to_owned())]);
+
+        // Sent from a thread of its own, the ones without a daemon come back to be shown as toasts
+        let client = Builder::address(address).unwrap().build().unwrap();
+        let mut notifier_thread = DesktopNotifierThread::start(move || DesktopNotifier::with_connection(client));
+        let mut missing_notifier_thread = DesktopNotifierThread::start(|| None);
+        assert!(notifier_thread.send("Break is over", ""));
+        assert!(missing_notifier_thread.send("Break is over", ""));
+        let mut undelivered = Vec::new();
+        while notifier_thread.is_waiting() || missing_notifier_thread.is_waiting() {
+            undelivered.extend(notifier_thread.undelivered());
+            undelivered.extend(missing_notifier_thread.undelivered());
+            sleep(Duration::from_millis(5));
+        }
+        assert_eq!(received.lock().unwrap().len(), 2);
+        assert_eq!(undelivered, vec![("Break is over".to_owned(), String::new())]);
+
+        dbus_daemon.kill().unwrap();
+    }
+
+    #[test]
+    fn test_status_output() {
+        let mut tags = vec![Tag::new("Say \"hi\""), Tag::new("Reading")];
+        tags[0].time_segments.push(TimeSegment::from_times(datetime!(2026-10-19 9:00 +0), None));
+        tags[0].is_active_segment = true;
+        let duration_display = DurationDisplay { format: DurationFormat::HoursMinutes, precision: 2 };
+        let clock_format = ClockFormat { hour_format: HourFormat::TwentyFourHour, date_format: DateFormat::YearMonthDay };
+        let now = datetime!(2026-10-19 10:30 +0);
+
+        let running = running_tags(&tags);
+        assert_eq!(status_output(&running, now, duration_display, clock_format, StatusFormat::Text), "Say \"hi\" 1:30");
+        assert_eq!(status_output(&running, now, duration_display, clock_format, StatusFormat::Waybar),
+                   r#"{"text":"Say \"hi\" 1:30","tooltip":"Say \"hi\" since 09:00","class":"running"}"#);
+        assert_eq!(status_output(&[], now, duration_display, clock_format, StatusFormat::Waybar),
+                   r#"{"text":"","tooltip":"","class":"idle"}"#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_control_socket() {
+        let path = std::env::temp_dir().join(format!("daily-time-keeper-test-{}.sock", std::process::id()));
+        let mut time_manager = TimeManager::new();
+        time_manager.is_rounding_on = false;
+        time_manager.add_tag("Writing").unwrap();
+        time_manager.add_tag("Reading").unwrap();
+        time_manager.control_server = Some(ControlServer::start(&path, || ()).unwrap());
+        assert!(ControlServer::start(&path, || ()).is_err());
+
+        let client_path = path.clone();
+        let client = std::thread::spawn(move || {
+            let add_request = request_line(&["add", "2026-10-19", "9:05 pm", "10:35 pm", "Reading"].map(str::to_owned));
+            ["start writing", "status", "start 1", "add 2026-10-19 9:00 10:30 Reading", "stop", "list", "stop Reading", "dance", &add_request]
+                .map(|request| send_request(&client_path, request).unwrap())
+        });
+        while !client.is_finished() {
+            time_manager.service_control_socket();
+            sleep(Duration::from_millis(5));
+        }
+        let replies = client.join().unwrap();
+
+        assert_eq!(replies[0], Ok("started Writing\n".to_owned()));
+        assert!(replies[1].as_ref().unwrap().starts_with("Writing\t"));
+        assert_eq!(replies[2], Err("Writing is already running".to_owned()));
+        assert_eq!(replies[3], Ok("added 1.50 to Reading\n".to_owned()));
+        assert_eq!(replies[4], Ok("stopped Writing\n".to_owned()));
+        assert_eq!(replies[5], Ok("1\tWriting\tstopped\n2\tReading\tstopped\n".to_owned()));
+        assert_eq!(replies[6], Err("Reading isn't running".to_owned()));
+        assert!(replies[7].is_err());
+        assert_eq!(replies[8], Ok("added 1.50 to Reading\n".to_owned()));
+        assert!((time_manager.tags[1].total_time - 3.0).abs() < f64::EPSILON);
+
+        // Tab separated arguments keep their spaces
+        assert_eq!(ControlRequest::parse("add\t2026-10-19\t9:05 pm\t10:35 pm\tClient A / Meetings\n"), Ok(ControlRequest::AddSegment {
+            date: "2026-10-19".to_owned(),
+            start_time: "9:05 pm".to_owned(),
+            end_time: "10:35 pm".to_owned(),
+            tag: "Client A / Meetings".to_owned(),
+        }));
+        assert_eq!(ControlRequest::parse("start\tClient A"), Ok(ControlRequest::Start("Client A".to_owned())));
+        assert_eq!(ControlRequest::parse("stop  Client A"), Ok(ControlRequest::Stop(Some("Client A".to_owned()))));
+
+        time_manager.control_server = None;
+        assert!(!path.exists());
+    }
+
+    #[cfg(feature = "http-api")]
+    #[test]
+    fn test_http_api() {
+        use std::io::{Read, Write};
+        use std::net::TcpStream;
+
+        fn http_request(port: u16, method: &str, path: &str, token: &str) -> (u16, serde_json::Value) {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            write!(stream, "{method} {path} HTTP/1.0\r\nHost: localhost\r\nAuthorization: Bearer {token}\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+
+            let (head, body) = response.split_once("\r\n\r\n").unwrap();
+            let status = head.split_whitespace().nth(1).unwrap().parse::<u16>().unwrap();
+            (status, serde_json::from_str(body).unwrap())
+        }
+
+        let mut time_manager = TimeManager::new();
+        time_manager.is_rounding_on = false;
+        time_manager.add_tag("Writing").unwrap();
+        time_manager.tags[0].time_segments.push(TimeSegment::from_times(datetime!(2026-10-12 9:00 +0), Some(datetime!(2026-10-12 10:30 +0))));
+        time_manager.tags[0].calculate_total();
+        time_manager.http_api_server = Some(HttpApiServer::start(0, "secret", || ()).unwrap());
+        let port = time_manager.http_api_server.as_ref().unwrap().port();
+
+        let client = std::thread::spawn(move || {
+            [
+                ("GET", "/tags", "wrong"),
+                ("GET", "/tags", "secret"),
+                ("POST", "/tags/1/start", "secret"),
+                ("POST", "/tags/1/start", "secret"),
+                ("GET", "/segments?tag=1&from=2026%2D10%2D12&to=2026-10-12", "secret"),
+                ("GET", "/reports?period=week", "secret"),
+                ("POST", "/stop", "secret"),
+                ("POST", "/tags/7/stop", "secret"),
+                ("GET", "/tags/1/start", "secret"),
+            ].map(|(method, path, token)| http_request(port, method, path, token))
+        });
+        while !client.is_finished() {
+            time_manager.service_http_api();
+            sleep(Duration::from_millis(5));
+        }
+        let responses = client.join().unwrap();
+
+        assert_eq!(responses[0].0, 401);
+        assert_eq!(responses[1].0, 200);
+        assert_eq!(responses[1].1[0]["name"], "Writing");
+        assert_eq!(responses[1].1[0]["is_running"], false);
+        assert_eq!(responses[2].0, 200);
+        assert_eq!(responses[2].1["is_running"], true);
+        assert_eq!(responses[3].0, 409);
+        assert_eq!(responses[4].1.as_array().unwrap().len(), 1);
+        assert_eq!(responses[4].1[0]["hours"], 1.5);
+        assert_eq!(responses[4].1[0]["start_time"], "2026-10-12T09:00:00Z");
+        assert_eq!(responses[5].1["rows"][0]["period"], "2026-W42");
+        assert_eq!(responses[5].1["total"], 1.5);
+        assert_eq!(responses[6].1[0]["id"], 1);
+        assert!(!time_manager.tags[0].is_active_segment);
+        assert_eq!(responses[7].0, 404);
+        assert_eq!(responses[8].0, 405);
+
+        assert!(is_token_match("secret", "secret"));
+        assert!(!is_token_match("secreT", "secret") && !is_token_match("secret2", "secret"));
+        assert_eq!(percent_decode("Client+A%2FMeetings%20%C3%A4"), Some("Client A/Meetings ä".to_owned()));
+        assert_eq!(percent_decode("100%"), None);
+        let token = generate_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_token());
+    }
 }
@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+use eframe::egui::{Align2, Area, Context, Frame, Id};
+#[cfg(feature = "desktop-notifications")]
+use crate::desktop_notification::*;
+
+const TOAST_LIFETIME: Duration = Duration::from_secs(8);
+
+struct Toast {
+    summary: String,
+    body: String,
+    created_at: Instant,
+}
+
+pub struct Notifications {
+    toasts: Vec<Toast>,
+    // Started on the first notification
+    #[cfg(feature = "desktop-notifications")]
+    desktop_notifier: Option<DesktopNotifierThread>,
+}
+
+impl Notifications {
+    pub fn new() -> Notifications {
+        Notifications {
+            toasts: Vec::new(),
+            #[cfg(feature = "desktop-notifications")]
+            desktop_notifier: None,
+        }
+    }
+
+    // Shown by the desktop where possible, otherwise as a toast in the window once the desktop turned it down
+    pub fn send(&mut self, summary: &str, body: &str) {
+        #[cfg(feature = "desktop-notifications")]
+        {
+            let desktop_notifier = self.desktop_notifier.get_or_insert_with(|| DesktopNotifierThread::start(DesktopNotifier::connect));
+            if desktop_notifier.send(summary, body) {
+                return;
+            }
+        }
+
+        self.push_toast(summary, body);
+    }
+
+    fn push_toast(&mut self, summary: &str, body: &str) {
+        self.toasts.push(Toast {
+            summary: summary.to_string(),
+            body: body.to_string(),
+            created_at: Instant::now(),
+        });
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        #[cfg(feature = "desktop-notifications")]
+        if let Some(desktop_notifier) = &mut self.desktop_notifier {
+            if desktop_notifier.is_waiting() {
+                ctx.request_repaint_after(Duration::from_millis(100));
+            }
+            for (summary, body) in desktop_notifier.undelivered() {
+                self.push_toast(&summary, &body);
+            }
+        }
+
+        self.toasts.retain(|toast| toast.created_at.elapsed() < TOAST_LIFETIME);
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        Area::new(Id::new("notification_toasts"))
+            .anchor(Align2::RIGHT_BOTTOM, [-10., -40.])
+            .show(ctx, |ui| {
+                for toast in &self.toasts {
+                    Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.strong(&toast.summary);
+                        ui.label(&toast.body);
+                    });
+                }
+            });
+
+        // Keep repainting so the toasts disappear once they expire
+        ctx.request_repaint_after(Duration::from_secs(1));
+    }
+}
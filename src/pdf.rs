@@ -0,0 +1,84 @@
+use std::fmt::Write;
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const FONT_SIZE: u32 = 10;
+const LINE_HEIGHT: u32 = 14;
+
+// The characters WinAnsiEncoding puts at 0x80 to 0x9F, where Latin-1 has control characters
+const WIN_ANSI_EXTRAS: [(char, u8); 27] = [
+    ('€', 0x80), ('‚', 0x82), ('ƒ', 0x83), ('„', 0x84), ('…', 0x85), ('†', 0x86), ('‡', 0x87), ('ˆ', 0x88), ('‰', 0x89),
+    ('Š', 0x8A), ('‹', 0x8B), ('Œ', 0x8C), ('Ž', 0x8E), ('‘', 0x91), ('’', 0x92), ('“', 0x93), ('”', 0x94), ('•', 0x95),
+    ('–', 0x96), ('—', 0x97), ('˜', 0x98), ('™', 0x99), ('š', 0x9A), ('›', 0x9B), ('œ', 0x9C), ('ž', 0x9E), ('Ÿ', 0x9F),
+];
+
+fn win_ansi_code(character: char) -> Option<u8> {
+    match u32::from(character) {
+        code @ (0x20..=0x7E | 0xA0..=0xFF) => u8::try_from(code).ok(),
+        _ => WIN_ANSI_EXTRAS.iter().find(|(extra, _)| *extra == character).map(|(_, code)| *code),
+    }
+}
+
+// Codes above ASCII are written as octal escapes so the content stream stays ASCII, characters outside of
+// WinAnsiEncoding become "?"
+fn escape_pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|character| match (character, win_ansi_code(character)) {
+            ('(' | ')' | '\\', _) => format!("\\{character}"),
+            (_, Some(code)) if code.is_ascii() => character.to_string(),
+            (_, Some(code)) => format!("\\{code:03o}"),
+            (_, None) => "?".to_owned(),
+        })
+        .collect()
+}
+
+// Renders monospaced text lines onto as many A4 pages as needed using the built-in Courier font
+pub fn text_to_pdf(lines: &[String]) -> Vec<u8> {
+    let lines_per_page = ((PAGE_HEIGHT - 2 * MARGIN) / LINE_HEIGHT) as usize;
+    let pages: Vec<&[String]> = if lines.is_empty() {
+        vec![&[]]
+    } else {
+        lines.chunks(lines_per_page).collect()
+    };
+
+    // Objects 1 to 3 are the catalog, the page tree and the font, followed by a page and content stream per page
+    let mut objects: Vec<String> = Vec::new();
+    let page_ids: Vec<usize> = (0..pages.len()).map(|page_index| 4 + page_index * 2).collect();
+    let kids: Vec<String> = page_ids.iter().map(|page_id| format!("{page_id} 0 R")).collect();
+
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_owned());
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+    objects.push("<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_owned());
+
+    for (page_lines, page_id) in pages.iter().zip(&page_ids) {
+        let mut content = String::new();
+        writeln!(content, "BT /F1 {FONT_SIZE} Tf {LINE_HEIGHT} TL {MARGIN} {} Td", PAGE_HEIGHT - MARGIN).unwrap();
+        for line in *page_lines {
+            writeln!(content, "({}) '", escape_pdf_text(line)).unwrap();
+        }
+        content.push_str("ET\n");
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            page_id + 1));
+        objects.push(format!("<< /Length {} >>\nstream\n{content}endstream", content.len()));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (object_index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        writeln!(pdf, "{} 0 obj\n{object}\nendobj", object_index + 1).unwrap();
+    }
+
+    let xref_offset = pdf.len();
+    writeln!(pdf, "xref\n0 {}", objects.len() + 1).unwrap();
+    pdf.push_str("0000000000 65535 f \n");
+    for offset in offsets {
+        writeln!(pdf, "{offset:010} 00000 n ").unwrap();
+    }
+    writeln!(pdf, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF", objects.len() + 1).unwrap();
+
+    pdf.into_bytes()
+}
@@ -0,0 +1,50 @@
+use serde::{Serialize, Deserialize};
+use time::{Date, Duration, OffsetDateTime};
+use crate::tag::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PomodoroSettings {
+    pub is_enabled: bool,
+    pub work_minutes: u32,
+    pub break_minutes: u32,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> PomodoroSettings {
+        PomodoroSettings {
+            is_enabled: false,
+            work_minutes: 25,
+            break_minutes: 5,
+        }
+    }
+}
+
+impl PomodoroSettings {
+    pub fn work_interval(self) -> Duration {
+        Duration::minutes(i64::from(self.work_minutes))
+    }
+
+    pub fn break_interval(self) -> Duration {
+        Duration::minutes(i64::from(self.break_minutes))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PomodoroPhase {
+    Work { tag_id: u32, ends_at: OffsetDateTime },
+    Break { ends_at: OffsetDateTime },
+}
+
+impl PomodoroPhase {
+    pub fn ends_at(self) -> OffsetDateTime {
+        match self {
+            PomodoroPhase::Work { ends_at, .. } | PomodoroPhase::Break { ends_at } => ends_at,
+        }
+    }
+}
+
+pub fn completed_pomodoros(tag: &Tag, day: Date) -> usize {
+    tag.finished_segments()
+        .filter(|segment| segment.is_pomodoro && segment.start_time.unwrap().date() == day)
+        .count()
+}
@@ -0,0 +1,20 @@
+use serde::{Serialize, Deserialize};
+use time::{Duration, OffsetDateTime, Time};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct ReminderSettings {
+    // Running time segments are stopped when the clock passes this time
+    pub auto_stop_time: Option<Time>,
+    pub long_run_hours: Option<f64>,
+    pub day_end_reminder_time: Option<Time>,
+}
+
+// The first moment after the given one that the clock shows the time of day, in the same offset
+pub fn next_time_of_day(after: OffsetDateTime, time_of_day: Time) -> OffsetDateTime {
+    let same_day = after.replace_time(time_of_day);
+    if same_day > after {
+        same_day
+    } else {
+        same_day + Duration::days(1)
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+use time::{Date, Duration};
+use crate::tag::*;
+use crate::tag_tree::*;
+use crate::time_segment::format_date;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    // First day of the period the date falls in, used as the sorting key of the report rows
+    fn period_start(self, date: Date) -> Date {
+        match self {
+            ReportPeriod::Day => date,
+            ReportPeriod::Week => date - Duration::days(i64::from(date.weekday().number_days_from_monday())),
+            ReportPeriod::Month => date.replace_day(1).unwrap(),
+        }
+    }
+
+    fn label(self, period_start: Date) -> String {
+        match self {
+            ReportPeriod::Day => format_date(period_start),
+            ReportPeriod::Week => {
+                let (year, week, _) = period_start.to_iso_week_date();
+                format!("{year}-W{week:02}")
+            },
+            ReportPeriod::Month => format!("{}-{:02}", period_start.year(), u8::from(period_start.month())),
+        }
+    }
+}
+
+pub struct ReportRow {
+    pub period_start: Date,
+    // Always in ISO format so exported reports sort and parse the same everywhere
+    pub label: String,
+    pub tag_hours: Vec<f64>,
+    pub total: f64,
+}
+
+#[derive(Clone, Copy)]
+pub struct ReportOptions {
+    pub period: ReportPeriod,
+    // Rounding of the displayed hours, earnings always follow the billing rounding
+    pub rounding_scale: Option<f32>,
+    pub billing_rounding_scale: Option<f32>,
+    // Parent tag columns include the hours of their subtags
+    pub is_rolled_up: bool,
+}
+
+pub struct Report {
+    pub tag_names: Vec<String>,
+    pub rows: Vec<ReportRow>,
+    pub tag_totals: Vec<f64>,
+    pub grand_total: f64,
+    pub tag_earnings: Vec<Option<(f64, String)>>,
+    pub billable_total: f64,
+    pub non_billable_total: f64,
+    pub earnings_per_currency: BTreeMap<String, f64>,
+}
+
+impl Report {
+    // Columns follow the tag hierarchy, parents before their subtags
+    pub fn new(tags: &[Tag], options: ReportOptions) -> Report {
+        let tag_order: Vec<usize> = tag_order(tags, false, TagSort::Manual).into_iter().map(|(tag_index, _)| tag_index).collect();
+        let mut hours_per_period: BTreeMap<Date, Vec<f64>> = BTreeMap::new();
+        let mut tag_earnings = vec![None; tags.len()];
+        let mut earnings_per_currency: BTreeMap<String, f64> = BTreeMap::new();
+
+        for (tag_index, tag) in tags.iter().enumerate() {
+            let mut earnings = 0f64;
+            for segment in tag.finished_segments() {
+                let period_start = options.period.period_start(segment.start_time.unwrap().date());
+                let tag_hours = hours_per_period.entry(period_start).or_insert_with(|| vec![0f64; tags.len()]);
+                tag_hours[tag_index] += round_hours(segment.hours_total, options.rounding_scale);
+                earnings += tag.billing.earnings(round_hours(segment.hours_total,
+                    tag.billing.rounding_scale(options.billing_rounding_scale)));
+            }
+
+            if tag.billing.is_billable {
+                *earnings_per_currency.entry(tag.billing.currency.clone()).or_insert(0f64) += earnings;
+                tag_earnings[tag_index] = Some((earnings, tag.billing.currency.clone()));
+            }
+        }
+
+        let in_tag_order = |values: &[f64]| -> Vec<f64> {
+            let values = if options.is_rolled_up { rolled_up(tags, values) } else { values.to_vec() };
+            tag_order.iter().map(|&tag_index| values[tag_index]).collect()
+        };
+
+        let mut own_totals = vec![0f64; tags.len()];
+        let rows: Vec<ReportRow> = hours_per_period.into_iter()
+            .map(|(period_start, tag_hours)| {
+                for (own_total, hours) in own_totals.iter_mut().zip(&tag_hours) {
+                    *own_total += hours;
+                }
+
+                ReportRow {
+                    period_start,
+                    label: options.period.label(period_start),
+                    total: tag_hours.iter().sum(),
+                    tag_hours: in_tag_order(&tag_hours),
+                }
+            })
+            .collect();
+
+        let billable_total = tags.iter()
+            .zip(&own_totals)
+            .filter(|(tag, _)| tag.billing.is_billable)
+            .map(|(_, hours)| hours)
+            .sum();
+        let grand_total = own_totals.iter().sum();
+
+        Report {
+            tag_names: tag_order.iter().map(|&tag_index| tag_path(tags, tag_index)).collect(),
+            grand_total,
+            rows,
+            tag_totals: in_tag_order(&own_totals),
+            tag_earnings: tag_order.iter().map(|&tag_index| tag_earnings[tag_index].clone()).collect(),
+            billable_total,
+            non_billable_total: grand_total - billable_total,
+            earnings_per_currency,
+        }
+    }
+}
+
+pub fn round_hours(hours: f64, rounding_scale: Option<f32>) -> f64 {
+    match rounding_scale {
+        Some(scale) if scale > 0.0 => {
+            let scale = f64::from(scale);
+            (hours / scale).round() * scale
+        },
+        _ => hours,
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::Write;
+use time::OffsetDateTime;
+use crate::clock::*;
+use crate::duration::*;
+use crate::tag::*;
+use crate::tag_tree::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StatusFormat {
+    // One line per change for polybar, i3blocks and the like
+    Text,
+    // Waybar's custom modules with `"return-type": "json"`
+    Waybar,
+}
+
+impl StatusFormat {
+    pub fn from_name(name: &str) -> Option<StatusFormat> {
+        match name {
+            "text" => Some(StatusFormat::Text),
+            "waybar" => Some(StatusFormat::Waybar),
+            _ => None,
+        }
+    }
+}
+
+// Paths and start times of the tags with an active segment, the earliest started first
+pub fn running_tags(tags: &[Tag]) -> Vec<(String, OffsetDateTime)> {
+    let mut running_tags: Vec<(String, OffsetDateTime)> = tags.iter()
+        .enumerate()
+        .filter(|(_, tag)| tag.is_active_segment && !tag.is_archived)
+        .map(|(tag_index, tag)| (tag_path(tags, tag_index), tag.time_segments.last().unwrap().start_time.unwrap()))
+        .collect();
+    running_tags.sort_by_key(|(_, start_time)| *start_time);
+
+    running_tags
+}
+
+// Empty text when nothing is running so the bar can hide the module
+pub fn status_output(running_tags: &[(String, OffsetDateTime)], now: OffsetDateTime, duration_display: DurationDisplay,
+                     clock_format: ClockFormat, format: StatusFormat) -> String {
+    let text = running_tags.iter()
+        .map(|(path, start_time)| {
+            let elapsed_hours = ((now - *start_time).as_seconds_f64() / 3600f64).max(0f64);
+            format!("{path} {}", duration_display.format(elapsed_hours))
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    match format {
+        StatusFormat::Text => text,
+        StatusFormat::Waybar => {
+            let tooltip = running_tags.iter()
+                .map(|(path, start_time)| format!("{path} since {}", clock_format.format_time(*start_time)))
+                .collect::<Vec<String>>()
+                .join("\n");
+            let class = if running_tags.is_empty() { "idle" } else { "running" };
+
+            format!("{{\"text\":{},\"tooltip\":{},\"class\":\"{class}\"}}", json_string(&text), json_string(&tooltip))
+        },
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            character if character.is_control() => write!(json, "\\u{:04x}", u32::from(character)).unwrap(),
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+
+    json
+}
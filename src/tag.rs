@@ -1,38 +1,181 @@
+use crate::billing::*;
+use crate::budget::*;
+use crate::i18n::*;
+use crate::report::round_hours;
+use crate::tag_tree::tag_path;
 use crate::time_segment::*;
+use serde::{Serialize, Deserialize};
+use time::{Date, OffsetDateTime};
 
+#[derive(Serialize, Deserialize)]
+pub struct SerializedTag {
+    #[serde(default)]
+    pub id: u32,
+    #[serde(default)]
+    pub parent_id: Option<u32>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub icon: String,
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+    #[serde(default)]
+    pub is_collapsed: bool,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub is_pinned: bool,
+    #[serde(default)]
+    pub billing: Billing,
+    #[serde(default)]
+    pub budget: Option<Budget>,
+    // Kept across restarts so the warning isn't sent again for the same period
+    #[serde(with = "time::serde::rfc3339::option", default, skip_serializing_if = "Option::is_none")]
+    pub budget_warning_sent_at: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub time_segments: Vec<SerializedTimeSegment>,
+    #[serde(default)]
+    pub history: Vec<SerializedTimeSegment>,
+}
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct Tag {
+    pub id: u32,
+    pub parent_id: Option<u32>,
     pub name: String,
+    pub icon: String,
+    pub color: Option<[u8; 3]>,
+    pub is_collapsed: bool,
+    pub is_archived: bool,
+    pub is_pinned: bool,
     pub time_segments: Vec<TimeSegment>,
+    pub history: Vec<TimeSegment>,
     pub is_active_segment: bool,
     pub total_time: f64,
+    pub billing: Billing,
+    pub budget: Option<Budget>,
+    pub is_settings_open: bool,
+    pub budget_warning_sent_at: Option<OffsetDateTime>,
+    pub is_long_run_warning_sent: bool,
+    pub is_renaming: bool,
+    pub rename_field: String,
 }
 
 impl Tag {
     pub fn new(name: &str) -> Tag {
-        let name = name.to_string();
-        let mut _self = Tag {
-            name,
+        Tag {
+            id: 0,
+            parent_id: None,
+            name: name.to_string(),
+            icon: String::new(),
+            color: None,
+            is_collapsed: false,
+            is_archived: false,
+            is_pinned: false,
             time_segments: Vec::new(),
+            history: Vec::new(),
             is_active_segment: false,
             total_time: 0f64,
-        };
+            billing: Billing::default(),
+            budget: None,
+            is_settings_open: false,
+            budget_warning_sent_at: None,
+            is_long_run_warning_sent: false,
+            is_renaming: false,
+            rename_field: String::new(),
+        }
+    }
+
+    pub fn from_serialized(serialized_tag: &SerializedTag) -> Tag {
+        let mut tag = Tag::new(&serialized_tag.name);
+        tag.id = serialized_tag.id;
+        tag.parent_id = serialized_tag.parent_id;
+        tag.icon.clone_from(&serialized_tag.icon);
+        tag.color = serialized_tag.color;
+        tag.is_collapsed = serialized_tag.is_collapsed;
+        tag.is_archived = serialized_tag.is_archived;
+        tag.is_pinned = serialized_tag.is_pinned;
+        tag.time_segments = serialized_tag.time_segments.iter().map(TimeSegment::from_serialized).collect();
+        tag.history = serialized_tag.history.iter().map(TimeSegment::from_serialized).collect();
+        tag.billing = serialized_tag.billing.clone();
+        tag.budget = serialized_tag.budget;
+        tag.budget_warning_sent_at = serialized_tag.budget_warning_sent_at;
+        tag.is_active_segment = tag.time_segments.last().is_some_and(|segment| segment.end_time.is_none());
+        tag.calculate_total();
 
-        _self
+        tag
     }
 
+    pub fn to_serialized(&self) -> SerializedTag {
+        SerializedTag {
+            id: self.id,
+            parent_id: self.parent_id,
+            name: self.name.clone(),
+            icon: self.icon.clone(),
+            color: self.color,
+            is_collapsed: self.is_collapsed,
+            is_archived: self.is_archived,
+            is_pinned: self.is_pinned,
+            billing: self.billing.clone(),
+            budget: self.budget,
+            budget_warning_sent_at: self.budget_warning_sent_at,
+            time_segments: self.time_segments.iter().map(TimeSegment::to_serialized).collect(),
+            history: self.history.iter().map(TimeSegment::to_serialized).collect(),
+        }
+    }
+
+    // A running segment stays in the session until it's stopped
     pub fn clear_session(&mut self) {
-        self.time_segments.clear();
-        self.is_active_segment = false;
+        let (finished_segments, running_segments): (Vec<TimeSegment>, Vec<TimeSegment>) = self.time_segments.drain(..)
+            .partition(|segment| segment.end_time.is_some());
+        self.history.extend(finished_segments);
+        self.time_segments = running_segments;
         self.total_time = 0f64;
     }
 
+    pub fn finished_segments(&self) -> impl Iterator<Item = &TimeSegment> {
+        self.history.iter()
+            .chain(self.time_segments.iter())
+            .filter(|segment| segment.end_time.is_some())
+    }
+
+    pub fn display_name(&self) -> String {
+        if self.icon.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.icon, self.name)
+        }
+    }
+
+    pub fn last_used(&self) -> Option<OffsetDateTime> {
+        self.history.iter()
+            .chain(self.time_segments.iter())
+            .filter_map(|segment| segment.start_time)
+            .max()
+    }
+
+    // Including the running segment so far
+    pub fn hours_since(&self, first_day: Date, now: OffsetDateTime) -> f64 {
+        self.history.iter()
+            .chain(self.time_segments.iter())
+            .filter(|segment| segment.start_time.unwrap().date() >= first_day)
+            .map(|segment| segment.elapsed_hours(now))
+            .sum()
+    }
+
+    pub fn session_earnings(&self, global_rounding_scale: Option<f32>) -> f64 {
+        let rounding_scale = self.billing.rounding_scale(global_rounding_scale);
+        self.time_segments.iter()
+            .map(|segment| self.billing.earnings(round_hours(segment.hours_total, rounding_scale)))
+            .sum()
+    }
+
     pub fn start_time_segment(&mut self, is_rounding_on: bool, minute_rounding_scale: f32) {
-        if self.is_active_segment == false {
+        if self.is_active_segment {
+            println!("Active segment already exists");
+        } else {
             let new_segment = TimeSegment::new(is_rounding_on, minute_rounding_scale);
             self.time_segments.push(new_segment);
             self.is_active_segment = true;
-        } else {
-            println!("Active segment already exists");
         }
     }
 
@@ -44,13 +187,125 @@ impl Tag {
             .record_end_time(is_rounding_on, minute_rounding_scale);
     }
 
+    pub fn toggle_time_segment(&mut self, is_rounding_on: bool, minute_rounding_scale: f32) {
+        if self.is_active_segment {
+            self.end_time_segment(is_rounding_on, minute_rounding_scale);
+            self.calculate_total();
+        } else {
+            self.start_time_segment(is_rounding_on, minute_rounding_scale);
+        }
+    }
+
+    // Cuts the interval out of the session's segments, splitting the ones that span it, and returns the hours removed.
+    // Invoiced segments are left alone
+    pub fn cut_interval(&mut self, start: OffsetDateTime, end: OffsetDateTime) -> f64 {
+        let mut removed_hours = 0f64;
+        let mut segments = Vec::new();
+
+        for segment in self.time_segments.drain(..) {
+            let segment_start = segment.start_time.unwrap();
+            let is_overlapping = segment_start < end && segment.end_time.is_none_or(|segment_end| segment_end > start);
+            if !is_overlapping || segment.invoice_number.is_some() {
+                segments.push(segment);
+                continue;
+            }
+
+            if segment_start < start {
+                let mut before = TimeSegment::from_times(segment_start, Some(start));
+                before.note.clone_from(&segment.note);
+                segments.push(before);
+            }
+            if segment.end_time.is_none_or(|segment_end| segment_end > end) {
+                let mut after = TimeSegment::from_times(end, segment.end_time);
+                after.note.clone_from(&segment.note);
+                segments.push(after);
+            }
+            let overlap_end = segment.end_time.map_or(end, |segment_end| segment_end.min(end));
+            removed_hours += (overlap_end - segment_start.max(start)).as_seconds_f64() / 3600f64;
+        }
+
+        self.time_segments = segments;
+        self.calculate_total();
+
+        removed_hours
+    }
+
+    // Adds a finished segment among the session's segments in start order, in front of the active one
+    pub fn insert_segment(&mut self, segment: TimeSegment) {
+        let segment_start = segment.start_time.unwrap();
+        let index = self.time_segments.iter()
+            .position(|existing| existing.end_time.is_none() || existing.start_time.unwrap() > segment_start)
+            .unwrap_or(self.time_segments.len());
+        self.time_segments.insert(index, segment);
+        self.calculate_total();
+    }
+
+    // The session total including the time of the active segment so far
+    pub fn running_total(&self, now: OffsetDateTime) -> f64 {
+        self.time_segments.iter().map(|segment| segment.elapsed_hours(now)).sum()
+    }
+
     pub fn calculate_total(&mut self) {
         let mut running_time = 0f64;
 
-        for time_segment in self.time_segments.iter() {
+        for time_segment in &self.time_segments {
             running_time += time_segment.hours_total;
         }
 
         self.total_time = running_time;
     }
-}
\ No newline at end of file
+}
+
+// Trims the name and rejects it when it's empty or a sibling, archived ones included, has the same name in any case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagNameError {
+    Empty,
+    Taken(String),
+    TakenByArchived(String),
+}
+
+impl TagNameError {
+    pub fn message(&self, translator: &Translator) -> String {
+        match self {
+            TagNameError::Empty => translator.tr("tag-name-empty"),
+            TagNameError::Taken(name) => translator.tr_args("tag-name-taken", &[("name", name.as_str().into())]),
+            TagNameError::TakenByArchived(name) => translator.tr_args("tag-name-taken-archived", &[("name", name.as_str().into())]),
+        }
+    }
+}
+
+// Siblings are the tags under the same parent, so "Client A / Meetings" and "Client B / Meetings" can coexist
+pub fn validate_tag_name(tags: &[Tag], name: &str, parent_id: Option<u32>, renamed_index: Option<usize>) -> Result<String, TagNameError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TagNameError::Empty);
+    }
+
+    let lowercase_name = name.to_lowercase();
+    let duplicate = tags.iter()
+        .enumerate()
+        .find(|(tag_index, tag)| Some(*tag_index) != renamed_index && tag.parent_id == parent_id && tag.name.to_lowercase() == lowercase_name);
+    match duplicate {
+        Some((_, tag)) if tag.is_archived => Err(TagNameError::TakenByArchived(tag.name.clone())),
+        Some((_, tag)) => Err(TagNameError::Taken(tag.name.clone())),
+        None => Ok(name.to_owned()),
+    }
+}
+
+// Existing and archived tags whose name contains the text as (index, path, is_archived), the ones starting with it first
+pub fn tag_name_suggestions(tags: &[Tag], text: &str, count: usize) -> Vec<(usize, String, bool)> {
+    let text = text.trim().to_lowercase();
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let mut suggestions: Vec<usize> = (0..tags.len())
+        .filter(|&tag_index| tags[tag_index].name.to_lowercase().contains(&text))
+        .collect();
+    suggestions.sort_by_key(|&tag_index| (!tags[tag_index].name.to_lowercase().starts_with(&text), tags[tag_index].name.to_lowercase()));
+
+    suggestions.into_iter()
+        .take(count)
+        .map(|tag_index| (tag_index, tag_path(tags, tag_index), tags[tag_index].is_archived))
+        .collect()
+}
@@ -0,0 +1,176 @@
+use std::cmp::Reverse;
+use serde::{Serialize, Deserialize};
+use crate::tag::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagSort {
+    #[default]
+    Manual,
+    Name,
+    RecentUse,
+}
+
+impl TagSort {
+    // Message id of the label
+    pub fn label(self) -> &'static str {
+        match self {
+            TagSort::Manual => "sort-manual",
+            TagSort::Name => "sort-name",
+            TagSort::RecentUse => "sort-recent-use",
+        }
+    }
+}
+
+fn parent_index(tags: &[Tag], tag: &Tag) -> Option<usize> {
+    let parent_id = tag.parent_id?;
+    tags.iter().position(|parent| parent.id == parent_id && parent.id != tag.id)
+}
+
+// Pinned tags come first, the rest follows the sort with the manual order as the tie breaker
+fn child_indices(tags: &[Tag], parent: Option<usize>, sort: TagSort) -> Vec<usize> {
+    let mut children: Vec<usize> = (0..tags.len())
+        .filter(|&tag_index| parent_index(tags, &tags[tag_index]) == parent)
+        .collect();
+
+    match sort {
+        TagSort::Manual => children.sort_by_key(|&tag_index| !tags[tag_index].is_pinned),
+        TagSort::Name => children.sort_by_key(|&tag_index| (!tags[tag_index].is_pinned, tags[tag_index].name.to_lowercase())),
+        TagSort::RecentUse => children.sort_by_key(|&tag_index| (!tags[tag_index].is_pinned, Reverse(tags[tag_index].last_used()))),
+    }
+
+    children
+}
+
+pub fn has_children(tags: &[Tag], tag_index: usize) -> bool {
+    tags.iter().any(|tag| parent_index(tags, tag) == Some(tag_index))
+}
+
+// Indices of the tag's ancestors starting with its parent, guarded against parent cycles
+pub fn ancestor_indices(tags: &[Tag], tag_index: usize) -> Vec<usize> {
+    let mut ancestors = Vec::new();
+    let mut current = parent_index(tags, &tags[tag_index]);
+
+    while let Some(ancestor) = current {
+        if ancestor == tag_index || ancestors.contains(&ancestor) {
+            break;
+        }
+        ancestors.push(ancestor);
+        current = parent_index(tags, &tags[ancestor]);
+    }
+
+    ancestors
+}
+
+pub fn is_descendant(tags: &[Tag], tag_index: usize, ancestor_index: usize) -> bool {
+    ancestor_indices(tags, tag_index).contains(&ancestor_index)
+}
+
+// Depth first order of the tags as (index, depth), leaving out archived tags and the children of collapsed tags
+// when `is_visible_only`
+pub fn tag_order(tags: &[Tag], is_visible_only: bool, sort: TagSort) -> Vec<(usize, usize)> {
+    let mut order = Vec::new();
+    let mut is_visited = vec![false; tags.len()];
+    let mut stack: Vec<(usize, usize, bool)> = child_indices(tags, None, sort).into_iter()
+        .rev()
+        .map(|tag_index| (tag_index, 0, false))
+        .collect();
+
+    loop {
+        while let Some((tag_index, depth, is_hidden)) = stack.pop() {
+            if is_visited[tag_index] {
+                continue;
+            }
+            is_visited[tag_index] = true;
+            let is_hidden = is_hidden || (is_visible_only && tags[tag_index].is_archived);
+            if !is_hidden {
+                order.push((tag_index, depth));
+            }
+
+            let is_children_hidden = is_hidden || (is_visible_only && tags[tag_index].is_collapsed);
+            for child_index in child_indices(tags, Some(tag_index), sort).into_iter().rev() {
+                stack.push((child_index, depth + 1, is_children_hidden));
+            }
+        }
+
+        // Tags caught in a parent cycle are never reached from a root, so they are added at the top level
+        match is_visited.iter().position(|is_tag_visited| !is_tag_visited) {
+            Some(tag_index) => stack.push((tag_index, 0, false)),
+            None => break,
+        }
+    }
+
+    order
+}
+
+// Adds every tag's own value onto all of its ancestors
+pub fn rolled_up(tags: &[Tag], values: &[f64]) -> Vec<f64> {
+    let mut rolled_up_values = values.to_vec();
+
+    for (tag_index, value) in values.iter().enumerate() {
+        for ancestor in ancestor_indices(tags, tag_index) {
+            rolled_up_values[ancestor] += value;
+        }
+    }
+
+    rolled_up_values
+}
+
+pub fn tag_path(tags: &[Tag], tag_index: usize) -> String {
+    let mut names: Vec<String> = ancestor_indices(tags, tag_index).iter()
+        .rev()
+        .map(|&ancestor| tags[ancestor].display_name())
+        .collect();
+    names.push(tags[tag_index].display_name());
+
+    names.join(" / ")
+}
+
+// Moves the children of a tag that is about to be removed up to the removed tag's parent
+pub fn reparent_children(tags: &mut [Tag], tag_index: usize) {
+    let removed_id = tags[tag_index].id;
+    let new_parent_id = tags[tag_index].parent_id;
+
+    for tag in tags.iter_mut() {
+        if tag.parent_id == Some(removed_id) && tag.id != removed_id {
+            tag.parent_id = new_parent_id;
+        }
+    }
+}
+
+// Tags that can become the parent of the tag without creating a cycle or a sibling of the same name, as (id, path) pairs
+pub fn parent_candidates(tags: &[Tag], tag_index: usize) -> Vec<(u32, String)> {
+    tag_order(tags, false, TagSort::Manual).into_iter()
+        .map(|(candidate_index, _)| candidate_index)
+        .filter(|&candidate_index| candidate_index != tag_index && !is_descendant(tags, candidate_index, tag_index))
+        .filter(|&candidate_index| {
+            let candidate_id = Some(tags[candidate_index].id);
+            candidate_id == tags[tag_index].parent_id || is_free_name_under(tags, tag_index, candidate_id)
+        })
+        .map(|candidate_index| (tags[candidate_index].id, tag_path(tags, candidate_index)))
+        .collect()
+}
+
+pub fn is_free_name_under(tags: &[Tag], tag_index: usize, parent_id: Option<u32>) -> bool {
+    validate_tag_name(tags, &tags[tag_index].name, parent_id, Some(tag_index)).is_ok()
+}
+
+// Archiving takes the subtags along while unarchiving brings back the ancestors so the tag is visible again
+pub fn set_archived(tags: &mut [Tag], tag_index: usize, is_archived: bool) {
+    let affected_indices: Vec<usize> = if is_archived {
+        (0..tags.len()).filter(|&other_index| is_descendant(tags, other_index, tag_index)).collect()
+    } else {
+        ancestor_indices(tags, tag_index)
+    };
+
+    for affected_index in affected_indices.into_iter().chain(std::iter::once(tag_index)) {
+        tags[affected_index].is_archived = is_archived;
+    }
+}
+
+// Moves a tag to the position of another one in the manual order, before it when moving up and after it when moving down
+pub fn move_tag(tags: &mut Vec<Tag>, from_index: usize, to_index: usize) {
+    if from_index != to_index {
+        let tag = tags.remove(from_index);
+        tags.insert(to_index, tag);
+    }
+}
@@ -1,42 +1,95 @@
-use time::{OffsetDateTime, Time};
+use time::{Date, Month, OffsetDateTime, Time};
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializedTimeSegment {
+    #[serde(with = "time::serde::rfc3339")]
+    pub start_time: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    pub end_time: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    #[serde(default)]
+    pub invoice_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_pomodoro: bool,
+}
 
 pub struct TimeSegment {
     pub start_time: Option<OffsetDateTime>,
     pub end_time: Option<OffsetDateTime>,
-    pub start_time_hour_field: String,
-    pub start_time_minute_field: String,
-    pub end_time_hour_field: String,
-    pub end_time_minute_field: String,
+    pub start_time_field: String,
+    pub end_time_field: String,
     pub hours_total: f64,
+    pub note: String,
+    pub invoice_number: Option<u32>,
+    // Ended by the pomodoro timer at the end of a full work interval
+    pub is_pomodoro: bool,
 }
 
 impl TimeSegment {
     pub fn new(is_rounding_on: bool, minute_rounding_scale: f32) -> TimeSegment {
-        let mut _self = TimeSegment {
+        let mut segment = TimeSegment {
             start_time: None,
             end_time: None,
-            start_time_hour_field: "".to_owned(),
-            start_time_minute_field: "".to_owned(),
-            end_time_hour_field: "".to_owned(),
-            end_time_minute_field: "".to_owned(),
+            start_time_field: String::new(),
+            end_time_field: String::new(),
             hours_total: 0f64,
+            note: String::new(),
+            invoice_number: None,
+            is_pomodoro: false,
         };
 
         let mut current_time = OffsetDateTime::now_local().ok();
 
         if is_rounding_on {
             let current_time_hms = current_time.unwrap().to_hms();
-            let rounded_time = _self.round_time(
+            let rounded_time = segment.round_time(
                 (current_time_hms.0, current_time_hms.1), minute_rounding_scale);
             let offset_rounded_time = current_time.unwrap().replace_time(Time::from_hms(rounded_time.0, rounded_time.1, 0).unwrap());
             current_time = Some(offset_rounded_time);
         }
-        _self.start_time = current_time;
-        let start_time_formatted = format_time_hour_minute(_self.start_time.unwrap());
-        _self.start_time_hour_field = start_time_formatted.0;
-        _self.start_time_minute_field = start_time_formatted.1;
+        segment.start_time = current_time;
+
+        segment
+    }
+
+    pub fn from_times(start_time: OffsetDateTime, end_time: Option<OffsetDateTime>) -> TimeSegment {
+        let mut segment = TimeSegment {
+            start_time: Some(start_time),
+            end_time,
+            start_time_field: String::new(),
+            end_time_field: String::new(),
+            hours_total: 0f64,
+            note: String::new(),
+            invoice_number: None,
+            is_pomodoro: false,
+        };
+
+        if end_time.is_some() {
+            segment.calculate_total_hours();
+        }
 
-        _self
+        segment
+    }
+
+    pub fn from_serialized(serialized_segment: &SerializedTimeSegment) -> TimeSegment {
+        let mut segment = TimeSegment::from_times(serialized_segment.start_time, serialized_segment.end_time);
+        segment.note.clone_from(&serialized_segment.note);
+        segment.invoice_number = serialized_segment.invoice_number;
+        segment.is_pomodoro = serialized_segment.is_pomodoro;
+
+        segment
+    }
+
+    pub fn to_serialized(&self) -> SerializedTimeSegment {
+        SerializedTimeSegment {
+            start_time: self.start_time.unwrap(),
+            end_time: self.end_time,
+            note: self.note.clone(),
+            invoice_number: self.invoice_number,
+            is_pomodoro: self.is_pomodoro,
+        }
     }
 
     pub fn record_end_time(&mut self, is_rounding_on: bool, minute_rounding_scale: f32) {
@@ -51,9 +104,6 @@ impl TimeSegment {
         }
 
         self.end_time = current_time;
-        let end_time_formatted = format_time_hour_minute(self.end_time.unwrap());
-        self.end_time_hour_field = end_time_formatted.0;
-        self.end_time_minute_field = end_time_formatted.1;
 
         self.calculate_total_hours();
     }
@@ -63,12 +113,22 @@ impl TimeSegment {
         self.hours_total = time_duration.as_seconds_f64() / 3600f64;
     }
 
+    // Finished segments keep their recorded hours while an active one counts up to now, starting at zero when the
+    // rounded start time is still ahead
+    pub fn elapsed_hours(&self, now: OffsetDateTime) -> f64 {
+        match self.end_time {
+            Some(_) => self.hours_total,
+            None => ((now - self.start_time.unwrap()).as_seconds_f64() / 3600f64).max(0f64),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::unused_self)]
     pub fn round_time(&self, time_to_be_rounded: (u8, u8), minute_rounding_scale: f32) -> (u8, u8) {
         let minute_accuracy = (60.0 * minute_rounding_scale).floor();
         let mut rounded_time = time_to_be_rounded;
 
         let mut minutes = time_to_be_rounded.1;
-        minutes = ((minutes as f32 / minute_accuracy + 0.5).floor() * minute_accuracy) as u8;
+        minutes = ((f32::from(minutes) / minute_accuracy + 0.5).floor() * minute_accuracy) as u8;
 
         if minutes >= 60 {
             rounded_time.0 += 1;
@@ -84,6 +144,15 @@ impl TimeSegment {
     }
 }
 
-fn format_time_hour_minute(time_stamp: OffsetDateTime) -> (String, String) {
-    (time_stamp.to_hms().0.to_string(), time_stamp.to_hms().1.to_string())
-}
\ No newline at end of file
+pub fn format_date(date: Date) -> String {
+    format!("{}-{:02}-{:02}", date.year(), u8::from(date.month()), date.day())
+}
+
+pub fn parse_date(date_text: &str) -> Option<Date> {
+    let mut parts = date_text.trim().splitn(3, '-');
+    let year = parts.next()?.parse::<i32>().ok()?;
+    let month = Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day = parts.next()?.parse::<u8>().ok()?;
+
+    Date::from_calendar_date(year, month, day).ok()
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use time::{Date, Duration};
+use crate::tag::*;
+
+// Message ids of the day names
+pub const WEEKDAY_NAMES: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct WorkSchedule {
+    // Expected working hours indexed from Monday
+    pub expected_hours: [f64; 7],
+}
+
+impl Default for WorkSchedule {
+    fn default() -> WorkSchedule {
+        WorkSchedule {
+            expected_hours: [8.0, 8.0, 8.0, 8.0, 8.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl WorkSchedule {
+    pub fn expected_hours_on(&self, date: Date) -> f64 {
+        self.expected_hours[usize::from(date.weekday().number_days_from_monday())]
+    }
+
+    // Overtime (positive) or undertime (negative) from the first tracked day up to and including today. Today only adds
+    // the overtime already worked, its undertime counts once the day is over
+    pub fn balance(&self, tags: &[Tag], today: Date) -> f64 {
+        let worked_hours = worked_hours_per_day(tags);
+        let Some(first_day) = worked_hours.keys().next().copied() else {
+            return 0.0;
+        };
+
+        let mut balance = 0.0;
+        let mut day = first_day;
+        while day <= today {
+            let day_balance = worked_hours.get(&day).copied().unwrap_or(0.0) - self.expected_hours_on(day);
+            balance += if day == today { day_balance.max(0.0) } else { day_balance };
+            day += Duration::days(1);
+        }
+
+        balance
+    }
+}
+
+pub fn worked_hours_per_day(tags: &[Tag]) -> BTreeMap<Date, f64> {
+    let mut worked_hours: BTreeMap<Date, f64> = BTreeMap::new();
+
+    for tag in tags {
+        for segment in tag.finished_segments() {
+            *worked_hours.entry(segment.start_time.unwrap().date()).or_insert(0.0) += segment.hours_total;
+        }
+    }
+
+    worked_hours
+}